@@ -6,7 +6,6 @@ pub(crate) struct Config {
     //pub admins: Vec<String>,
     pub email: String,
 }
-use std::collections::HashMap;
 
 use webex::{
     self,
@@ -42,7 +41,9 @@ async fn main() {
             webex::ActivityType::Message(webex::MessageActivity::Posted) => {
                 respond_to_message(&webex, &config, &event).await
             }
-            webex::ActivityType::AdaptiveCardSubmit => handle_adaptive_card(&webex, &event).await,
+            webex::ActivityType::AdaptiveCardSubmit(_) => {
+                handle_adaptive_card(&webex, &event).await
+            }
             _ => {
                 //dbg!(event);
             }
@@ -53,14 +54,13 @@ async fn main() {
 
 async fn handle_adaptive_card(webex: &webex::Webex, event: &webex::Event) {
     // get attachmentactions
-    let actions: webex::types::AttachmentAction =
-        match webex.get(&event.try_global_id().unwrap()).await {
-            Ok(a) => a,
-            Err(e) => {
-                println!("Error: {}", e);
-                return;
-            }
-        };
+    let actions = match webex.get_attachment_action_for_event(event).await {
+        Ok(a) => a,
+        Err(e) => {
+            println!("Error: {}", e);
+            return;
+        }
+    };
     let which_card = actions.inputs.as_ref().and_then(|inputs| inputs.get("id"));
     match which_card {
         None => println!(
@@ -162,13 +162,13 @@ async fn respond_to_message(webex: &webex::Webex, config: &Config, event: &webex
     );
     body.add_element(CardElement::action_set().add_action_to_set(
         webex::adaptive_card::Action::Submit {
-            data: Some(HashMap::from([("id".into(), "init".into())])),
+            data: Some(serde_json::json!({"id": "init"})),
             title: Some("Submit".into()),
             style: None,
         },
     ));
     let card = AdaptiveCard::new().add_body(body);
-    reply.add_attachment(card);
+    reply.push_attachment(card);
     let _resp = webex
         .send_message(&reply)
         .await
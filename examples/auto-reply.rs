@@ -1,8 +1,3 @@
-use std::env;
-
-const BOT_ACCESS_TOKEN: &str = "BOT_ACCESS_TOKEN";
-const BOT_EMAIL: &str = "BOT_EMAIL";
-
 ///
 /// # Autoreply
 ///
@@ -11,7 +6,7 @@ const BOT_EMAIL: &str = "BOT_EMAIL";
 ///
 /// # Usage
 ///
-/// BOT_ACCESS_TOKEN="<token>" BOT_EMAIL="botname@webex.bot" cargo run --example auto-reply
+/// BOT_ACCESS_TOKEN="<token>" cargo run --example auto-reply
 ///
 /// You can obtain a bot token by logging into the [Cisco Webex developer site](https://developer.webex.com/), then
 ///
@@ -25,15 +20,18 @@ const BOT_EMAIL: &str = "BOT_EMAIL";
 
 #[tokio::main]
 async fn main() {
-    let token = env::var(BOT_ACCESS_TOKEN)
-        .unwrap_or_else(|_| panic!("{} not specified in environment", BOT_ACCESS_TOKEN));
-    let bot_email = env::var(BOT_EMAIL)
-        .unwrap_or_else(|_| panic!("{} not specified in environment", BOT_EMAIL));
-
-    let webex = webex::Webex::new(token.as_str()).await;
+    let webex = webex::Webex::from_env().unwrap().await;
     let mut event_stream = webex.event_stream().await.expect("event stream");
 
     while let Ok(event) = event_stream.next().await {
+        // Skip our own messages, recognised by person ID (this works for integrations too,
+        // unlike comparing email addresses)
+        // In practice, this shouldn't happen since bots can't see messages
+        // that don't specifically mention them (i.e., appears in the special
+        // "mentions" field).
+        if webex.event_is_mine(&event).await.expect("bot details") {
+            continue;
+        }
         // Dig out the useful bit
         if event.activity_type() == webex::ActivityType::Message(webex::MessageActivity::Posted) {
             // The event stream doesn't contain the message -- you have to go fetch it
@@ -41,18 +39,10 @@ async fn main() {
                 .get::<webex::Message>(&event.try_global_id().unwrap())
                 .await
             {
-                match &msg.person_email {
-                    // Reply as long as it doesn't appear to be our own message
-                    // In practice, this shouldn't happen since bots can't see messages
-                    // that don't specifically mention them (i.e., appears in the special
-                    // "mentions" field).
-                    Some(sender) if sender != bot_email.as_str() => {
-                        let mut reply = webex::types::MessageOut::from(&msg);
-                        reply.text = Some(format!("{}, you said: {}", sender, msg.text.unwrap()));
-                        webex.send_message(&reply).await.unwrap();
-                    }
-                    _ => (),
-                }
+                let sender = msg.person_email.as_deref().unwrap_or("someone");
+                let mut reply = webex::types::MessageOut::from(&msg);
+                reply.text = Some(format!("{}, you said: {}", sender, msg.text.unwrap()));
+                webex.send_message(&reply).await.unwrap();
             }
         }
     }
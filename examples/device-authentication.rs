@@ -27,7 +27,7 @@ async fn main() {
 
     let w = Webex::new(&token).await;
 
-    let rooms = w.get_all_rooms().await.expect("obtaning rooms");
+    let rooms = w.list_all_rooms().await.expect("obtaning rooms");
 
     println!("{rooms:#?}");
 }
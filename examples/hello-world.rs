@@ -1,6 +1,5 @@
 use std::env;
 
-const BOT_ACCESS_TOKEN: &str = "BOT_ACCESS_TOKEN";
 const DEST_EMAIL: &str = "DEST_EMAIL";
 
 /// # Hello World
@@ -23,12 +22,10 @@ const DEST_EMAIL: &str = "DEST_EMAIL";
 
 #[tokio::main]
 async fn main() {
-    let token = env::var(BOT_ACCESS_TOKEN)
-        .unwrap_or_else(|_| panic!("{} not specified in environment", BOT_ACCESS_TOKEN));
     let to_email = env::var(DEST_EMAIL)
         .unwrap_or_else(|_| panic!("{} not specified in environment", DEST_EMAIL));
 
-    let webex = webex::Webex::new(token.as_str()).await;
+    let webex = webex::Webex::from_env().unwrap().await;
     let text = format!("Hello, {}", to_email);
 
     let msg_to_send = webex::types::MessageOut {
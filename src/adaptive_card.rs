@@ -2,21 +2,25 @@
 #![allow(clippy::return_self_not_must_use)]
 //! Adaptive Card implementation
 //!
-//! [Webex Teams currently supports only version 1.1](https://developer.webex.com/docs/cards)
+//! [Webex currently supports versions up to 1.4](https://developer.webex.com/docs/cards); cards
+//! are created with version 1.1 unless set otherwise (see [`CardVersion`]).
 //!
 //! More info about the schema can be found [here](https://adaptivecards.io/explorer/)
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{borrow::Cow, collections::HashMap};
+
+/// Schema URL set on new cards
+pub const ADAPTIVE_CARD_SCHEMA: &str = "http://adaptivecards.io/schemas/adaptive-card.json";
 
 /// Adaptive Card structure for message attachment
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 pub struct AdaptiveCard {
-    /// Must be "`AdaptiveCard`"
+    /// Must be "`AdaptiveCard`", set by [`AdaptiveCard::new()`] and not meant to be changed.
     #[serde(rename = "type")]
     pub card_type: String,
     /// Schema version that this card requires. If a client is lower than this version, the fallbackText will be rendered.
-    /// Maximum version is 1.1
+    /// Maximum version supported by Webex is 1.4
     #[serde(default = "default_version")] // Workaround for Webex not always providing it :/
     pub version: String,
     /// The card elements to show in the primary card region.
@@ -41,7 +45,10 @@ pub struct AdaptiveCard {
     /// <http://adaptivecards.io/schemas/adaptive-card.json>
     #[serde(rename = "$schema")]
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub schema: Option<String>,
+    pub schema: Option<Cow<'static, str>>,
+    /// Specifies the background image of the card.
+    #[serde(rename = "backgroundImage", skip_serializing_if = "Option::is_none")]
+    pub background_image: Option<BackgroundImage>,
 }
 
 impl AdaptiveCard {
@@ -57,8 +64,113 @@ impl AdaptiveCard {
             fallback_text: None,
             min_height: None,
             lang: None,
-            schema: Some("http://adaptivecards.io/schemas/adaptive-card.json".to_string()),
+            schema: Some(Cow::Borrowed(ADAPTIVE_CARD_SCHEMA)),
+            background_image: None,
+        }
+    }
+
+    /// Deserialize a card from its JSON representation, e.g. the `content` of a received
+    /// attachment.
+    pub fn from_json(s: &str) -> Result<Self, crate::error::Error> {
+        Ok(serde_json::from_str(s)?)
+    }
+
+    /// Deserialize a card from a JSON template, replacing every `{{key}}` placeholder with the
+    /// corresponding value from `data`.
+    ///
+    /// The template is parsed before substituting, and placeholders are only replaced inside
+    /// JSON strings, so values can't change the structure of the card. A placeholder outside a
+    /// string makes the template invalid JSON. Placeholders without a value in `data` are left
+    /// as-is.
+    ///
+    /// Values are not HTML-escaped: cards render text as plain text or Markdown, never HTML, so
+    /// escaping would show e.g. `&lt;` instead of `<`.
+    ///
+    /// # Errors
+    /// Returns [`Error::Json`](crate::error::Error::Json) if the template isn't valid JSON, or
+    /// the rendered template isn't a valid card.
+    pub fn from_template<S: std::hash::BuildHasher>(
+        template: &str,
+        data: &HashMap<String, String, S>,
+    ) -> Result<Self, crate::error::Error> {
+        fn render<S: std::hash::BuildHasher>(
+            value: &mut serde_json::Value,
+            data: &HashMap<String, String, S>,
+        ) {
+            match value {
+                serde_json::Value::String(s) => *s = render_str(s, data),
+                serde_json::Value::Array(items) => {
+                    for item in items {
+                        render(item, data);
+                    }
+                }
+                serde_json::Value::Object(fields) => {
+                    for field in fields.values_mut() {
+                        render(field, data);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        fn render_str<S: std::hash::BuildHasher>(
+            template: &str,
+            data: &HashMap<String, String, S>,
+        ) -> String {
+            let mut rendered = String::with_capacity(template.len());
+            let mut rest = template;
+            while let Some(start) = rest.find("{{") {
+                rendered.push_str(&rest[..start]);
+                let after = &rest[start + 2..];
+                let Some(end) = after.find("}}") else {
+                    rest = &rest[start..];
+                    break;
+                };
+                match data.get(after[..end].trim()) {
+                    Some(value) => rendered.push_str(value),
+                    None => rendered.push_str(&rest[start..start + end + 4]),
+                }
+                rest = &after[end + 2..];
+            }
+            rendered.push_str(rest);
+            rendered
         }
+
+        let mut card: serde_json::Value = serde_json::from_str(template)?;
+        render(&mut card, data);
+        Ok(serde_json::from_value(card)?)
+    }
+
+    /// Size of the card once serialized to JSON, in bytes
+    #[must_use]
+    pub fn serialized_size(&self) -> usize {
+        serde_json::to_string(self).unwrap_or_default().len()
+    }
+
+    /// Create new adaptive card targeting a specific schema version, e.g. `"1.3"` to use input
+    /// labels.
+    #[must_use]
+    pub fn new_with_version<T: Into<String>>(version: T) -> Self {
+        Self {
+            version: version.into(),
+            ..Self::new()
+        }
+    }
+
+    /// Sets the schema version of the card
+    ///
+    /// # Arguments
+    ///
+    /// * `v` - Version, either a [`CardVersion`] or a string such as `"1.2"`
+    pub fn set_version<T: Into<String>>(&mut self, v: T) -> Self {
+        self.version = v.into();
+        self.into()
+    }
+
+    /// Sets the background image of the card
+    pub fn set_background_image(&mut self, image: BackgroundImage) -> Self {
+        self.background_image = Some(image);
+        self.into()
     }
 
     /// Adds Element to body
@@ -99,22 +211,192 @@ impl AdaptiveCard {
         });
         self.into()
     }
+
+    /// Compare this card to a newer version of it, element by element.
+    ///
+    /// The comparison is shallow: body elements and actions are compared by index, and changes
+    /// within an element are only reported as that element having changed. An empty result
+    /// means both cards are identical.
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> Vec<CardDiff> {
+        let mut diffs = vec![];
+        if self.version != other.version {
+            diffs.push(CardDiff::VersionChanged(
+                self.version.clone(),
+                other.version.clone(),
+            ));
+        }
+        let old = self.body.as_deref().unwrap_or_default();
+        let new = other.body.as_deref().unwrap_or_default();
+        for i in 0..old.len().max(new.len()) {
+            match (old.get(i), new.get(i)) {
+                (Some(a), Some(b)) if a != b => {
+                    diffs.push(CardDiff::BodyElementChanged(i, b.clone()));
+                }
+                (None, Some(b)) => diffs.push(CardDiff::BodyElementAdded(i, b.clone())),
+                (Some(_), None) => diffs.push(CardDiff::BodyElementRemoved(i)),
+                _ => {}
+            }
+        }
+        let old = self.actions.as_deref().unwrap_or_default();
+        let new = other.actions.as_deref().unwrap_or_default();
+        for i in 0..old.len().max(new.len()) {
+            match (old.get(i), new.get(i)) {
+                (Some(a), Some(b)) if a != b => diffs.push(CardDiff::ActionChanged(i, b.clone())),
+                (None, Some(b)) => diffs.push(CardDiff::ActionAdded(i, b.clone())),
+                (Some(_), None) => diffs.push(CardDiff::ActionRemoved(i)),
+                _ => {}
+            }
+        }
+        let strip = |card: &Self| Self {
+            version: String::new(),
+            body: None,
+            actions: None,
+            ..card.clone()
+        };
+        if strip(self) != strip(other) {
+            diffs.push(CardDiff::PropertiesChanged);
+        }
+        diffs
+    }
+
+    /// Check the actions of the card, including those of nested elements and cards, see
+    /// [`Action::validate()`], and that the card version supports the features it uses.
+    ///
+    /// # Errors
+    /// Returns the first problem found.
+    pub fn validate(&self) -> Result<(), CardValidationError> {
+        self.select_action
+            .iter()
+            .map(AsRef::as_ref)
+            .chain(self.actions.iter().flatten())
+            .try_for_each(Action::validate)?;
+        self.body
+            .iter()
+            .flatten()
+            .try_for_each(CardElement::validate)?;
+        let version = self
+            .version
+            .split_once('.')
+            .and_then(|(major, minor)| Some((major.parse().ok()?, minor.parse().ok()?)));
+        let required = self.min_version();
+        if version.is_some_and(|v: (u32, u32)| v < required.number()) {
+            return Err(CardValidationError::VersionTooLow(
+                self.version.clone(),
+                required.into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The lowest version supporting every element and action of the card. Cards shown by
+    /// `Action.ShowCard` declare their own version, so they aren't included.
+    fn min_version(&self) -> CardVersion {
+        let versions = self
+            .select_action
+            .iter()
+            .map(AsRef::as_ref)
+            .chain(self.actions.iter().flatten())
+            .map(Action::min_version)
+            .chain(self.body.iter().flatten().map(CardElement::min_version));
+        max_version(versions)
+    }
+}
+
+/// A problem found by [`AdaptiveCard::validate()`]
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum CardValidationError {
+    /// The URL of an `Action.OpenUrl` could not be parsed
+    #[error("invalid URL {0:?}: {1}")]
+    InvalidUrl(String, String),
+    /// The URL of an `Action.OpenUrl` doesn't use `http` or `https`, e.g. `javascript:`
+    #[error("URL {0:?} must use http or https")]
+    UnsupportedScheme(String),
+    /// The card uses a feature (e.g. `isRequired`) that needs a more recent version than the one
+    /// it declares; the second value is the minimum version
+    #[error("card version {0} is too low, {1} or above is required")]
+    VersionTooLow(String, String),
+}
+
+/// A difference between two cards, see [`AdaptiveCard::diff()`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum CardDiff {
+    /// The version changed from the first to the second value
+    VersionChanged(String, String),
+    /// An element was added to the body at the given index
+    BodyElementAdded(usize, CardElement),
+    /// The body element at the given index was removed
+    BodyElementRemoved(usize),
+    /// The body element at the given index was replaced by a different one
+    BodyElementChanged(usize, CardElement),
+    /// An action was added at the given index
+    ActionAdded(usize, Action),
+    /// The action at the given index was removed
+    ActionRemoved(usize),
+    /// The action at the given index was replaced by a different one
+    ActionChanged(usize, Action),
+    /// Any other property of the card (e.g. `fallback_text` or `select_action`) changed
+    PropertiesChanged,
 }
 
 impl From<&Self> for AdaptiveCard {
-    #[must_use]
     fn from(item: &Self) -> Self {
         item.clone()
     }
 }
 
 impl From<&mut Self> for AdaptiveCard {
-    #[must_use]
     fn from(item: &mut Self) -> Self {
         item.clone()
     }
 }
 
+/// Adaptive Card schema versions supported by Webex
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord, Hash)]
+pub enum CardVersion {
+    /// Version 1.1, the default
+    V11,
+    /// Version 1.2: `RichTextBlock`, `Media`, `ActionSet`
+    V12,
+    /// Version 1.3: input labels and validation (`isRequired`, `errorMessage`)
+    V13,
+    /// Version 1.4: `Action.Execute`; the highest version currently supported by Webex
+    V14,
+}
+
+impl CardVersion {
+    const fn number(self) -> (u32, u32) {
+        match self {
+            Self::V11 => (1, 1),
+            Self::V12 => (1, 2),
+            Self::V13 => (1, 3),
+            Self::V14 => (1, 4),
+        }
+    }
+}
+
+/// The highest of `versions`, or 1.1 if there are none
+fn max_version(versions: impl Iterator<Item = CardVersion>) -> CardVersion {
+    versions.fold(CardVersion::V11, Ord::max)
+}
+
+impl From<CardVersion> for &'static str {
+    fn from(v: CardVersion) -> Self {
+        match v {
+            CardVersion::V11 => "1.1",
+            CardVersion::V12 => "1.2",
+            CardVersion::V13 => "1.3",
+            CardVersion::V14 => "1.4",
+        }
+    }
+}
+
+impl From<CardVersion> for String {
+    fn from(v: CardVersion) -> Self {
+        <&'static str>::from(v).to_string()
+    }
+}
+
 /// Card element types
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(tag = "type")]
@@ -130,12 +412,21 @@ pub enum CardElement {
         /// Style hint for Container.
         #[serde(skip_serializing_if = "Option::is_none")]
         style: Option<ContainerStyle>,
+        /// Specifies the background image.
+        #[serde(rename = "backgroundImage", skip_serializing_if = "Option::is_none")]
+        background_image: Option<BackgroundImage>,
         /// Defines how the content should be aligned vertically within the container.
         #[serde(
             rename = "verticalContentAlignment",
             skip_serializing_if = "Option::is_none"
         )]
         vertical_content_alignment: Option<VerticalContentAlignment>,
+        /// Determines whether the element should bleed through its parent's padding.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        bleed: Option<bool>,
+        /// Specifies the minimum height of the container in pixels, like "80px".
+        #[serde(rename = "minHeight", skip_serializing_if = "Option::is_none")]
+        min_height: Option<String>,
         /// Specifies the height of the element.
         #[serde(skip_serializing_if = "Option::is_none")]
         height: Option<Height>,
@@ -157,6 +448,18 @@ pub enum CardElement {
         /// An Action that will be invoked when the `ColumnSet` is tapped or selected.
         #[serde(rename = "selectAction", skip_serializing_if = "Option::is_none")]
         select_action: Option<Action>,
+        /// Style hint for `ColumnSet`.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        style: Option<ContainerStyle>,
+        /// Determines whether the element should bleed through its parent's padding.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        bleed: Option<bool>,
+        /// Controls how the columns are horizontally positioned, when they don't fill the set.
+        #[serde(
+            rename = "horizontalAlignment",
+            skip_serializing_if = "Option::is_none"
+        )]
+        horizontal_alignment: Option<HorizontalAlignment>,
         /// A unique identifier associated with the item.
         #[serde(skip_serializing_if = "Option::is_none")]
         id: Option<String>,
@@ -219,7 +522,8 @@ pub enum CardElement {
         color: Option<Color>,
         /// Controls the horizontal text alignment.
         #[serde(
-            rename = "HorizontalAlignment",
+            rename = "horizontalAlignment",
+            alias = "HorizontalAlignment",
             skip_serializing_if = "Option::is_none"
         )]
         horizontal_alignment: Option<HorizontalAlignment>,
@@ -228,7 +532,7 @@ pub enum CardElement {
         is_subtle: Option<bool>,
         /// Specifies the maximum number of lines to display.
         #[serde(rename = "maxLines", skip_serializing_if = "Option::is_none")]
-        max_lines: Option<u64>,
+        max_lines: Option<u32>,
         /// Specifies the font type
         #[serde(rename = "fontType", skip_serializing_if = "Option::is_none")]
         font_type: Option<FontType>,
@@ -300,6 +604,15 @@ pub enum CardElement {
     InputText {
         /// Unique identifier for the value. Used to identify collected input when the Submit action is performed.
         id: String,
+        /// Label for this input (card version 1.3 and above).
+        #[serde(skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+        /// Whether or not this input is required (card version 1.3 and above).
+        #[serde(rename = "isRequired", skip_serializing_if = "Option::is_none")]
+        is_required: Option<bool>,
+        /// Error message to display when entered input is invalid (card version 1.3 and above).
+        #[serde(rename = "errorMessage", skip_serializing_if = "Option::is_none")]
+        error_message: Option<String>,
         /// Description of the input desired. Displayed when no text has been input.
         #[serde(skip_serializing_if = "Option::is_none")]
         placeholder: Option<String>,
@@ -308,7 +621,7 @@ pub enum CardElement {
         is_multiline: Option<bool>,
         /// Hint of maximum length characters to collect (may be ignored by some clients).
         #[serde(rename = "maxLength", skip_serializing_if = "Option::is_none")]
-        max_length: Option<u64>,
+        max_length: Option<u32>,
         /// Text Input Style
         #[serde(skip_serializing_if = "Option::is_none")]
         style: Option<TextInputStyle>,
@@ -334,6 +647,15 @@ pub enum CardElement {
     InputNumber {
         /// Unique identifier for the value. Used to identify collected input when the Submit action is performed.
         id: String,
+        /// Label for this input (card version 1.3 and above).
+        #[serde(skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+        /// Whether or not this input is required (card version 1.3 and above).
+        #[serde(rename = "isRequired", skip_serializing_if = "Option::is_none")]
+        is_required: Option<bool>,
+        /// Error message to display when entered input is invalid (card version 1.3 and above).
+        #[serde(rename = "errorMessage", skip_serializing_if = "Option::is_none")]
+        error_message: Option<String>,
         /// Description of the input desired. Displayed when no selection has been made.
         #[serde(skip_serializing_if = "Option::is_none")]
         placeholder: Option<String>,
@@ -362,6 +684,15 @@ pub enum CardElement {
     InputDate {
         /// Unique identifier for the value. Used to identify collected input when the Submit action is performed.
         id: String,
+        /// Label for this input (card version 1.3 and above).
+        #[serde(skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+        /// Whether or not this input is required (card version 1.3 and above).
+        #[serde(rename = "isRequired", skip_serializing_if = "Option::is_none")]
+        is_required: Option<bool>,
+        /// Error message to display when entered input is invalid (card version 1.3 and above).
+        #[serde(rename = "errorMessage", skip_serializing_if = "Option::is_none")]
+        error_message: Option<String>,
         /// Description of the input desired. Displayed when no selection has been made.
         #[serde(skip_serializing_if = "Option::is_none")]
         placeholder: Option<String>,
@@ -390,6 +721,15 @@ pub enum CardElement {
     InputTime {
         /// Unique identifier for the value. Used to identify collected input when the Submit action is performed.
         id: String,
+        /// Label for this input (card version 1.3 and above).
+        #[serde(skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+        /// Whether or not this input is required (card version 1.3 and above).
+        #[serde(rename = "isRequired", skip_serializing_if = "Option::is_none")]
+        is_required: Option<bool>,
+        /// Error message to display when entered input is invalid (card version 1.3 and above).
+        #[serde(rename = "errorMessage", skip_serializing_if = "Option::is_none")]
+        error_message: Option<String>,
         /// Hint of maximum value expressed in HH:MM (may be ignored by some clients).
         #[serde(skip_serializing_if = "Option::is_none")]
         max: Option<String>,
@@ -415,6 +755,15 @@ pub enum CardElement {
     InputToggle {
         /// Unique identifier for the value. Used to identify collected input when the Submit action is performed.
         id: String,
+        /// Label for this input (card version 1.3 and above).
+        #[serde(skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+        /// Whether or not this input is required (card version 1.3 and above).
+        #[serde(rename = "isRequired", skip_serializing_if = "Option::is_none")]
+        is_required: Option<bool>,
+        /// Error message to display when entered input is invalid (card version 1.3 and above).
+        #[serde(rename = "errorMessage", skip_serializing_if = "Option::is_none")]
+        error_message: Option<String>,
         /// The initial selected value. If you want the toggle to be initially on, set this to the value of valueOn‘s value.
         #[serde(skip_serializing_if = "Option::is_none")]
         value: Option<String>,
@@ -445,6 +794,15 @@ pub enum CardElement {
         choices: Vec<Choice>,
         /// Unique identifier for the value. Used to identify collected input when the Submit action is performed.
         id: String,
+        /// Label for this input (card version 1.3 and above).
+        #[serde(skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+        /// Whether or not this input is required (card version 1.3 and above).
+        #[serde(rename = "isRequired", skip_serializing_if = "Option::is_none")]
+        is_required: Option<bool>,
+        /// Error message to display when entered input is invalid (card version 1.3 and above).
+        #[serde(rename = "errorMessage", skip_serializing_if = "Option::is_none")]
+        error_message: Option<String>,
         /// Allow multiple choices to be selected.
         #[serde(rename = "isMultiSelect", skip_serializing_if = "Option::is_none")]
         is_multi_select: Option<bool>,
@@ -472,25 +830,160 @@ pub enum CardElement {
         /// Specifies the height of the element.
         #[serde(skip_serializing_if = "Option::is_none")]
         height: Option<Height>,
+        /// A unique identifier associated with the item.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
     },
 }
 
 impl From<&Self> for CardElement {
-    #[must_use]
     fn from(item: &Self) -> Self {
         item.clone()
     }
 }
 
 impl From<&mut Self> for CardElement {
-    #[must_use]
     fn from(item: &mut Self) -> Self {
         item.clone()
     }
 }
 
+/// Creates a `ColumnSet` with the given columns
+impl From<Vec<Column>> for CardElement {
+    fn from(columns: Vec<Column>) -> Self {
+        let mut set = Self::column_set();
+        if let Self::ColumnSet { columns: c, .. } = &mut set {
+            *c = columns;
+        }
+        set
+    }
+}
+
 /// Functions for Card Element
 impl CardElement {
+    /// Check the actions of the element and of the elements it contains
+    fn validate(&self) -> Result<(), CardValidationError> {
+        match self {
+            Self::Container {
+                items,
+                select_action,
+                ..
+            } => {
+                select_action.iter().try_for_each(Action::validate)?;
+                items.iter().try_for_each(Self::validate)
+            }
+            Self::ColumnSet {
+                columns,
+                select_action,
+                ..
+            } => {
+                select_action.iter().try_for_each(Action::validate)?;
+                columns.iter().try_for_each(|column| {
+                    column.select_action.iter().try_for_each(Action::validate)?;
+                    column.items.iter().try_for_each(Self::validate)
+                })
+            }
+            Self::Image { select_action, .. } => {
+                select_action.iter().try_for_each(Action::validate)
+            }
+            Self::InputText { inline_action, .. } => {
+                inline_action.iter().try_for_each(Action::validate)
+            }
+            Self::ActionSet { actions, .. } => actions.iter().try_for_each(Action::validate),
+            _ => Ok(()),
+        }
+    }
+
+    /// The lowest card version supporting this element and the ones it contains, e.g. 1.3 for
+    /// inputs with a label or `isRequired`
+    fn min_version(&self) -> CardVersion {
+        match self {
+            Self::Container {
+                items,
+                select_action,
+                ..
+            } => max_version(
+                items
+                    .iter()
+                    .map(Self::min_version)
+                    .chain(select_action.iter().map(Action::min_version)),
+            ),
+            Self::ColumnSet {
+                columns,
+                select_action,
+                ..
+            } => max_version(
+                columns
+                    .iter()
+                    .flat_map(|column| &column.items)
+                    .map(Self::min_version)
+                    .chain(
+                        columns
+                            .iter()
+                            .filter_map(|column| column.select_action.as_ref())
+                            .chain(select_action)
+                            .map(Action::min_version),
+                    ),
+            ),
+            Self::Image { select_action, .. } => {
+                max_version(select_action.iter().map(Action::min_version))
+            }
+            Self::ActionSet { actions, .. } => max_version(
+                actions
+                    .iter()
+                    .map(Action::min_version)
+                    .chain([CardVersion::V12]),
+            ),
+            Self::InputText {
+                label,
+                is_required,
+                error_message,
+                inline_action,
+                ..
+            } => {
+                let inline = max_version(inline_action.iter().map(Action::min_version));
+                if label.is_some() || is_required.is_some() || error_message.is_some() {
+                    inline.max(CardVersion::V13)
+                } else {
+                    inline
+                }
+            }
+            Self::InputNumber {
+                label,
+                is_required,
+                error_message,
+                ..
+            }
+            | Self::InputDate {
+                label,
+                is_required,
+                error_message,
+                ..
+            }
+            | Self::InputTime {
+                label,
+                is_required,
+                error_message,
+                ..
+            }
+            | Self::InputToggle {
+                label,
+                is_required,
+                error_message,
+                ..
+            }
+            | Self::InputChoiceSet {
+                label,
+                is_required,
+                error_message,
+                ..
+            } if label.is_some() || is_required.is_some() || error_message.is_some() => {
+                CardVersion::V13
+            }
+            _ => CardVersion::V11,
+        }
+    }
+
     /// Create container
     #[must_use]
     pub const fn container() -> Self {
@@ -498,7 +991,10 @@ impl CardElement {
             items: vec![],
             select_action: None,
             style: None,
+            background_image: None,
             vertical_content_alignment: None,
+            bleed: None,
+            min_height: None,
             height: None,
             id: None,
             separator: None,
@@ -506,6 +1002,32 @@ impl CardElement {
         }
     }
 
+    /// Set Container or `ColumnSet` bleed, to extend it into the padding of its parent
+    pub fn set_bleed(&mut self, b: bool) -> Self {
+        match self {
+            Self::Container { bleed, .. } | Self::ColumnSet { bleed, .. } => {
+                *bleed = Some(b);
+            }
+            _ => {
+                log::warn!("Card does not have bleed field");
+            }
+        }
+        self.into()
+    }
+
+    /// Set Container minimum height, e.g. "80px"
+    pub fn set_min_height<T: Into<String>>(&mut self, h: T) -> Self {
+        match self {
+            Self::Container { min_height, .. } => {
+                *min_height = Some(h.into());
+            }
+            _ => {
+                log::warn!("Card does not have minHeight field");
+            }
+        }
+        self.into()
+    }
+
     /// Add element to Container
     pub fn add_element<T: Into<Self>>(&mut self, element: T) -> Self {
         if let Self::Container { items, .. } = self {
@@ -514,10 +1036,46 @@ impl CardElement {
         self.into()
     }
 
-    /// Set Container Style
+    /// Set Container or `ColumnSet` Style, see [`CardElement::set_style()`]
     pub fn set_container_style(&mut self, s: ContainerStyle) -> Self {
-        if let Self::Container { style, .. } = self {
-            *style = Some(s);
+        self.set_style(s)
+    }
+
+    /// Set Container background image
+    pub fn set_background_image(&mut self, image: BackgroundImage) -> Self {
+        match self {
+            Self::Container {
+                background_image, ..
+            } => {
+                *background_image = Some(image);
+            }
+            _ => {
+                log::warn!("Card does not have backgroundImage field");
+            }
+        }
+        self.into()
+    }
+
+    /// Set the horizontal alignment of a `TextBlock`, `Image` or `ColumnSet`
+    pub fn set_horizontal_alignment(&mut self, alignment: HorizontalAlignment) -> Self {
+        match self {
+            Self::TextBlock {
+                horizontal_alignment,
+                ..
+            }
+            | Self::Image {
+                horizontal_alignment,
+                ..
+            }
+            | Self::ColumnSet {
+                horizontal_alignment,
+                ..
+            } => {
+                *horizontal_alignment = Some(alignment);
+            }
+            _ => {
+                log::warn!("Card does not have horizontal alignment field");
+            }
         }
         self.into()
     }
@@ -539,6 +1097,9 @@ impl CardElement {
     pub fn input_text<T: Into<String>, S: Into<String>>(id: T, value: Option<S>) -> Self {
         Self::InputText {
             id: id.into(),
+            label: None,
+            is_required: None,
+            error_message: None,
             placeholder: None,
             is_multiline: None,
             max_length: None,
@@ -559,12 +1120,87 @@ impl CardElement {
         self.into()
     }
 
+    /// Create input.Number
+    #[must_use]
+    pub fn input_number<T: Into<String>>(
+        id: T,
+        min: Option<f64>,
+        max: Option<f64>,
+        value: Option<f64>,
+    ) -> Self {
+        Self::InputNumber {
+            id: id.into(),
+            label: None,
+            is_required: None,
+            error_message: None,
+            placeholder: None,
+            max,
+            min,
+            value,
+            height: None,
+            separator: None,
+            spacing: None,
+        }
+    }
+
+    /// Create input.Date
+    ///
+    /// Dates are expressed in ISO-8601 format, e.g. `2024-01-31`.
+    #[must_use]
+    pub fn input_date<T: Into<String>, S: Into<String>>(
+        id: T,
+        min: Option<S>,
+        max: Option<S>,
+        value: Option<S>,
+    ) -> Self {
+        Self::InputDate {
+            id: id.into(),
+            label: None,
+            is_required: None,
+            error_message: None,
+            placeholder: None,
+            max: max.map(Into::into),
+            min: min.map(Into::into),
+            value: value.map(Into::into),
+            height: None,
+            separator: None,
+            spacing: None,
+        }
+    }
+
+    /// Create input.Time
+    ///
+    /// Times are expressed in `HH:MM` format, e.g. `13:45`.
+    #[must_use]
+    pub fn input_time<T: Into<String>, S: Into<String>>(
+        id: T,
+        min: Option<S>,
+        max: Option<S>,
+        value: Option<S>,
+    ) -> Self {
+        Self::InputTime {
+            id: id.into(),
+            label: None,
+            is_required: None,
+            error_message: None,
+            max: max.map(Into::into),
+            min: min.map(Into::into),
+            value: value.map(Into::into),
+            height: None,
+            separator: None,
+            spacing: None,
+        }
+    }
+
     /// Create input.ChoiceSet
     #[must_use]
     pub fn input_choice_set<T: Into<String>, S: Into<String>>(id: T, value: Option<S>) -> Self {
         Self::InputChoiceSet {
             choices: vec![],
             id: id.into(),
+            label: None,
+            is_required: None,
+            error_message: None,
             is_multi_select: None,
             style: None,
             value: value.map(Into::into),
@@ -579,6 +1215,9 @@ impl CardElement {
     pub fn input_toggle<T: Into<String>>(id: T, value: bool) -> Self {
         Self::InputToggle {
             id: id.into(),
+            label: None,
+            is_required: None,
+            error_message: None,
             value: Some(value.to_string()),
             value_off: None,
             value_on: None,
@@ -589,10 +1228,24 @@ impl CardElement {
         }
     }
 
-    /// Set choiceSet Style
-    pub fn set_style(&mut self, s: ChoiceInputStyle) -> Self {
-        if let Self::InputChoiceSet { style, .. } = self {
-            *style = Some(s);
+    /// Set the style of a Container or `ColumnSet` ([`ContainerStyle`]), Image ([`ImageStyle`]),
+    /// `Input.Text` ([`TextInputStyle`]) or `Input.ChoiceSet` ([`ChoiceInputStyle`])
+    pub fn set_style<T: Into<ElementStyle>>(&mut self, s: T) -> Self {
+        match (&mut *self, s.into()) {
+            (
+                Self::Container { style, .. } | Self::ColumnSet { style, .. },
+                ElementStyle::Container(s),
+            ) => {
+                *style = Some(s);
+            }
+            (Self::Image { style, .. }, ElementStyle::Image(s)) => *style = Some(s),
+            (Self::InputText { style, .. }, ElementStyle::TextInput(s)) => *style = Some(s),
+            (Self::InputChoiceSet { style, .. }, ElementStyle::ChoiceInput(s)) => {
+                *style = Some(s);
+            }
+            (_, s) => {
+                log::warn!("Card does not have style field for {s:?}");
+            }
         }
         self.into()
     }
@@ -718,17 +1371,45 @@ impl CardElement {
         }
     }
 
+    /// Create imageSet
+    #[must_use]
+    pub const fn image_set() -> Self {
+        Self::ImageSet {
+            images: vec![],
+            image_size: None,
+            height: None,
+            id: None,
+            separator: None,
+            spacing: None,
+        }
+    }
+
+    /// Add image to imageSet
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - URL of the image to add
+    pub fn add_image_to_set<T: Into<String>>(&mut self, url: T) -> Self {
+        if let Self::ImageSet { images, .. } = self {
+            images.push(Self::image(url));
+        }
+        self.into()
+    }
+
+    /// Get the images of an imageSet, or `None` for other elements
+    #[must_use]
+    pub fn images(&self) -> Option<&[Self]> {
+        match self {
+            Self::ImageSet { images, .. } => Some(images),
+            _ => None,
+        }
+    }
+
     /// Add fact to factSet
     pub fn add_key_value<T: Into<String>, S: Into<String>>(&mut self, title: T, value: S) -> Self {
         match self {
-            Self::FactSet { facts, .. } => facts.push(Fact {
-                title: title.into(),
-                value: value.into(),
-            }),
-            Self::InputChoiceSet { choices, .. } => choices.push(Choice {
-                title: title.into(),
-                value: value.into(),
-            }),
+            Self::FactSet { facts, .. } => facts.push(Fact::new(title, value)),
+            Self::InputChoiceSet { choices, .. } => choices.push(Choice::new(title, value)),
             _ => {
                 log::warn!("Card does not have key value type field");
             }
@@ -736,12 +1417,31 @@ impl CardElement {
         self.into()
     }
 
+    /// Replace the facts of a factSet
+    pub fn set_facts(&mut self, f: Vec<Fact>) -> Self {
+        if let Self::FactSet { facts, .. } = self {
+            *facts = f;
+        }
+        self.into()
+    }
+
+    /// Replace the choices of a choiceSet
+    pub fn set_choices(&mut self, c: Vec<Choice>) -> Self {
+        if let Self::InputChoiceSet { choices, .. } = self {
+            *choices = c;
+        }
+        self.into()
+    }
+
     /// Create columnSet
     #[must_use]
     pub const fn column_set() -> Self {
         Self::ColumnSet {
             columns: vec![],
             select_action: None,
+            style: None,
+            bleed: None,
+            horizontal_alignment: None,
             id: None,
             separator: None,
             spacing: None,
@@ -756,6 +1456,50 @@ impl CardElement {
         self.into()
     }
 
+    /// Set Id
+    pub fn set_id<T: Into<String>>(&mut self, new_id: T) -> Self {
+        match self {
+            Self::Container { id, .. }
+            | Self::ColumnSet { id, .. }
+            | Self::FactSet { id, .. }
+            | Self::ImageSet { id, .. }
+            | Self::TextBlock { id, .. }
+            | Self::Image { id, .. }
+            | Self::ActionSet { id, .. } => {
+                *id = Some(new_id.into());
+            }
+            Self::InputText { id, .. }
+            | Self::InputNumber { id, .. }
+            | Self::InputDate { id, .. }
+            | Self::InputTime { id, .. }
+            | Self::InputToggle { id, .. }
+            | Self::InputChoiceSet { id, .. } => {
+                *id = new_id.into();
+            }
+        }
+        self.into()
+    }
+
+    /// Get Id
+    #[must_use]
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            Self::Container { id, .. }
+            | Self::ColumnSet { id, .. }
+            | Self::FactSet { id, .. }
+            | Self::ImageSet { id, .. }
+            | Self::TextBlock { id, .. }
+            | Self::Image { id, .. }
+            | Self::ActionSet { id, .. } => id.as_deref(),
+            Self::InputText { id, .. }
+            | Self::InputNumber { id, .. }
+            | Self::InputDate { id, .. }
+            | Self::InputTime { id, .. }
+            | Self::InputToggle { id, .. }
+            | Self::InputChoiceSet { id, .. } => Some(id),
+        }
+    }
+
     /// Set Separator
     pub fn set_separator(&mut self, s: bool) -> Self {
         match self {
@@ -775,7 +1519,73 @@ impl CardElement {
         self.into()
     }
 
-    /// Set Placeholder
+    /// Set Label (card version 1.3 and above)
+    pub fn set_label<T: Into<String>>(&mut self, s: T) -> Self {
+        match self {
+            Self::InputText { label, .. }
+            | Self::InputNumber { label, .. }
+            | Self::InputDate { label, .. }
+            | Self::InputTime { label, .. }
+            | Self::InputToggle { label, .. }
+            | Self::InputChoiceSet { label, .. } => {
+                *label = Some(s.into());
+            }
+            _ => {
+                log::warn!("Card does not have label field");
+            }
+        }
+        self.into()
+    }
+
+    /// Mark an input as required, with the error message to show when it is left empty.
+    /// Requires card version 1.3 or above, see [`AdaptiveCard::new_with_version()`];
+    /// [`AdaptiveCard::validate()`] rejects cards declaring an older version.
+    pub fn set_required<T: Into<String>>(&mut self, required: bool, message: T) -> Self {
+        match self {
+            Self::InputText {
+                is_required,
+                error_message,
+                ..
+            }
+            | Self::InputNumber {
+                is_required,
+                error_message,
+                ..
+            }
+            | Self::InputDate {
+                is_required,
+                error_message,
+                ..
+            }
+            | Self::InputTime {
+                is_required,
+                error_message,
+                ..
+            }
+            | Self::InputToggle {
+                is_required,
+                error_message,
+                ..
+            }
+            | Self::InputChoiceSet {
+                is_required,
+                error_message,
+                ..
+            } => {
+                *is_required = Some(required);
+                *error_message = Some(message.into());
+            }
+            _ => {
+                log::warn!("Card does not have isRequired field");
+            }
+        }
+        self.into()
+    }
+
+    /// Set the placeholder of an `Input.Text`, `Input.Number` or `Input.Date`.
+    ///
+    /// Other elements, including `Input.Time`, have no placeholder in the Adaptive Cards schema
+    /// and are left unchanged.
     pub fn set_placeholder(&mut self, s: Option<String>) -> Self {
         match self {
             Self::InputText { placeholder, .. }
@@ -784,12 +1594,42 @@ impl CardElement {
                 *placeholder = s;
             }
             _ => {
-                log::warn!("Card does not have placeholder field");
+                log::warn!(
+                    "Only Input.Text, Input.Number and Input.Date have a placeholder, ignoring it"
+                );
+            }
+        }
+        self.into()
+    }
+
+    /// Set the initial value of an `Input.Text`, `Input.Date` (YYYY-MM-DD), `Input.Time` (HH:MM),
+    /// `Input.Toggle` or `Input.ChoiceSet`. See [`Self::set_value_num()`] for `Input.Number`.
+    pub fn set_value_str(&mut self, v: &str) -> Self {
+        match self {
+            Self::InputText { value, .. }
+            | Self::InputDate { value, .. }
+            | Self::InputTime { value, .. }
+            | Self::InputToggle { value, .. }
+            | Self::InputChoiceSet { value, .. } => {
+                *value = Some(v.to_string());
+            }
+            _ => {
+                log::warn!("Card does not have a string value field");
             }
         }
         self.into()
     }
 
+    /// Set the initial value of an `Input.Number`
+    pub fn set_value_num(&mut self, v: f64) -> Self {
+        if let Self::InputNumber { value, .. } = self {
+            *value = Some(v);
+        } else {
+            log::warn!("Card does not have a number value field");
+        }
+        self.into()
+    }
+
     /// Set Spacing
     pub fn set_spacing(&mut self, s: Spacing) -> Self {
         match self {
@@ -814,6 +1654,7 @@ impl CardElement {
         Self::ActionSet {
             actions: vec![],
             height: None,
+            id: None,
         }
     }
 
@@ -831,47 +1672,67 @@ impl CardElement {
 pub struct Column {
     /// The card elements to render inside the Column.
     #[serde(default)]
-    items: Vec<CardElement>,
+    pub items: Vec<CardElement>,
     /// An Action that will be invoked when the Column is tapped or selected.
     #[serde(rename = "selectAction", skip_serializing_if = "Option::is_none")]
-    select_action: Option<Action>,
+    pub select_action: Option<Action>,
     /// Style hint for Column.
     #[serde(skip_serializing_if = "Option::is_none")]
-    style: Option<ContainerStyle>,
+    pub style: Option<ContainerStyle>,
+    /// Specifies the background image.
+    #[serde(rename = "backgroundImage", skip_serializing_if = "Option::is_none")]
+    pub background_image: Option<BackgroundImage>,
     /// Defines how the content should be aligned vertically within the column.
     #[serde(
         rename = "verticalContentAlignment",
         skip_serializing_if = "Option::is_none"
     )]
-    vertical_content_alignment: Option<VerticalContentAlignment>,
+    pub vertical_content_alignment: Option<VerticalContentAlignment>,
+    /// Determines whether the column should bleed through its parent's padding.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bleed: Option<bool>,
     /// When true, draw a separating line between this column and the previous column.
     #[serde(skip_serializing_if = "Option::is_none")]
-    separator: Option<bool>,
+    pub separator: Option<bool>,
     /// Controls the amount of spacing between this column and the preceding column.
     #[serde(skip_serializing_if = "Option::is_none")]
-    spacing: Option<Spacing>,
+    pub spacing: Option<Spacing>,
     /// "auto", "stretch", a number representing relative width of the column in the column group, or in version 1.1 and higher, a specific pixel width, like "50px".
     #[serde(skip_serializing_if = "Option::is_none")]
-    width: Option<serde_json::Value>,
+    pub width: Option<serde_json::Value>,
     /// A unique identifier associated with the item.
     #[serde(skip_serializing_if = "Option::is_none")]
-    id: Option<String>,
+    pub id: Option<String>,
 }
 
 impl From<&Self> for Column {
-    #[must_use]
     fn from(item: &Self) -> Self {
         item.clone()
     }
 }
 
 impl From<&mut Self> for Column {
-    #[must_use]
     fn from(item: &mut Self) -> Self {
         item.clone()
     }
 }
 
+/// Creates a column with the given items
+impl From<Vec<CardElement>> for Column {
+    fn from(items: Vec<CardElement>) -> Self {
+        Self {
+            items,
+            ..Self::new()
+        }
+    }
+}
+
+impl FromIterator<CardElement> for Column {
+    fn from_iter<I: IntoIterator<Item = CardElement>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
 impl Column {
     /// Creates new Column
     #[must_use]
@@ -880,7 +1741,9 @@ impl Column {
             items: vec![],
             select_action: None,
             style: None,
+            background_image: None,
             vertical_content_alignment: None,
+            bleed: None,
             separator: None,
             spacing: None,
             width: None,
@@ -894,6 +1757,12 @@ impl Column {
         self.into()
     }
 
+    /// Sets bleed, to extend the column into the padding of its parent
+    pub fn set_bleed(&mut self, b: bool) -> Self {
+        self.bleed = Some(b);
+        self.into()
+    }
+
     /// Sets separator
     pub fn set_separator(&mut self, s: bool) -> Self {
         self.separator = Some(s);
@@ -906,6 +1775,18 @@ impl Column {
         self.into()
     }
 
+    /// Sets background image
+    pub fn set_background_image(&mut self, image: BackgroundImage) -> Self {
+        self.background_image = Some(image);
+        self.into()
+    }
+
+    /// Sets style
+    pub fn set_style(&mut self, s: ContainerStyle) -> Self {
+        self.style = Some(s);
+        self.into()
+    }
+
     /// Sets width
     pub fn set_width<T: Into<String>>(&mut self, s: T) -> Self {
         self.width = Some(serde_json::Value::String(s.into()));
@@ -913,13 +1794,103 @@ impl Column {
     }
 }
 
+/// Specifies a background image. Acceptable formats are PNG, JPEG, and GIF.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(from = "BackgroundImageRepr")]
+pub struct BackgroundImage {
+    /// The URL (or data url) of the image.
+    pub url: String,
+    /// Describes how the image should fill the area.
+    #[serde(rename = "fillMode", skip_serializing_if = "Option::is_none")]
+    pub fill_mode: Option<FillMode>,
+    /// Describes how the image should be aligned if it must be cropped or if using repeat fill mode.
+    #[serde(
+        rename = "horizontalAlignment",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub horizontal_alignment: Option<HorizontalAlignment>,
+    /// Describes how the image should be aligned if it must be cropped or if using repeat fill mode.
+    #[serde(rename = "verticalAlignment", skip_serializing_if = "Option::is_none")]
+    pub vertical_alignment: Option<VerticalContentAlignment>,
+}
+
+impl BackgroundImage {
+    /// Creates new background image from a URL
+    #[must_use]
+    pub fn new<T: Into<String>>(url: T) -> Self {
+        Self {
+            url: url.into(),
+            fill_mode: None,
+            horizontal_alignment: None,
+            vertical_alignment: None,
+        }
+    }
+}
+
+/// The spec also allows a background image to be given as a plain URL.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BackgroundImageRepr {
+    Url(String),
+    Image {
+        url: String,
+        #[serde(rename = "fillMode", default)]
+        fill_mode: Option<FillMode>,
+        #[serde(rename = "horizontalAlignment", default)]
+        horizontal_alignment: Option<HorizontalAlignment>,
+        #[serde(rename = "verticalAlignment", default)]
+        vertical_alignment: Option<VerticalContentAlignment>,
+    },
+}
+
+impl From<BackgroundImageRepr> for BackgroundImage {
+    fn from(repr: BackgroundImageRepr) -> Self {
+        match repr {
+            BackgroundImageRepr::Url(url) => Self::new(url),
+            BackgroundImageRepr::Image {
+                url,
+                fill_mode,
+                horizontal_alignment,
+                vertical_alignment,
+            } => Self {
+                url,
+                fill_mode,
+                horizontal_alignment,
+                vertical_alignment,
+            },
+        }
+    }
+}
+
+/// Describes how a background image should fill the area.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FillMode {
+    Cover,
+    RepeatHorizontally,
+    RepeatVertically,
+    Repeat,
+}
+
 /// Describes a Fact in a `FactSet` as a key/value pair.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Fact {
     /// The title of the fact.
-    title: String,
+    pub title: String,
     /// The value of the fact.
-    value: String,
+    pub value: String,
+}
+
+impl Fact {
+    /// Create a new fact from a title and value
+    #[must_use]
+    pub fn new<T: Into<String>, S: Into<String>>(title: T, value: S) -> Self {
+        Self {
+            title: title.into(),
+            value: value.into(),
+        }
+    }
 }
 
 /// Available color options
@@ -1004,6 +1975,43 @@ pub enum Height {
     Stretch,
 }
 
+/// Style of any element, see [`CardElement::set_style()`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ElementStyle {
+    /// Style of a Container or `ColumnSet`
+    Container(ContainerStyle),
+    /// Style of an Image
+    Image(ImageStyle),
+    /// Style of an `Input.Text`
+    TextInput(TextInputStyle),
+    /// Style of an `Input.ChoiceSet`
+    ChoiceInput(ChoiceInputStyle),
+}
+
+impl From<ContainerStyle> for ElementStyle {
+    fn from(style: ContainerStyle) -> Self {
+        Self::Container(style)
+    }
+}
+
+impl From<ImageStyle> for ElementStyle {
+    fn from(style: ImageStyle) -> Self {
+        Self::Image(style)
+    }
+}
+
+impl From<TextInputStyle> for ElementStyle {
+    fn from(style: TextInputStyle) -> Self {
+        Self::TextInput(style)
+    }
+}
+
+impl From<ChoiceInputStyle> for ElementStyle {
+    fn from(style: ChoiceInputStyle) -> Self {
+        Self::ChoiceInput(style)
+    }
+}
+
 /// Image Style
 #[allow(missing_docs)]
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -1070,8 +2078,11 @@ pub enum Action {
     #[serde(rename = "Action.Submit")]
     Submit {
         /// Initial data that input fields will be combined with. These are essentially ‘hidden’ properties.
+        ///
+        /// Can be any JSON value, e.g. `serde_json::json!({"action": "approve", "item": {"id": 42}})`.
+        /// A `HashMap<String, String>` can be converted with `serde_json::to_value()`.
         #[serde(skip_serializing_if = "Option::is_none")]
-        data: Option<HashMap<String, String>>,
+        data: Option<serde_json::Value>,
         /// Label for button or link that represents this action.
         #[serde(skip_serializing_if = "Option::is_none")]
         title: Option<String>,
@@ -1091,6 +2102,26 @@ pub enum Action {
         #[serde(skip_serializing_if = "Option::is_none")]
         style: Option<ActionStyle>,
     },
+    /// Gathers input fields, merges with optional data field, and sends an event to the client.
+    /// Part of the Universal Action Model, which lets the bot refresh the card in place.
+    /// Requires a card version of 1.4 or higher.
+    #[serde(rename = "Action.Execute")]
+    Execute {
+        /// The card author-defined verb associated with this action.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        verb: Option<String>,
+        /// Initial data that input fields will be combined with. These are essentially ‘hidden’ properties.
+        ///
+        /// Can be any JSON value, like the `data` of [`Action::Submit`].
+        #[serde(skip_serializing_if = "Option::is_none")]
+        data: Option<serde_json::Value>,
+        /// Label for button or link that represents this action.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        /// Controls the style of an Action, which influences how the action is displayed, spoken, etc.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        style: Option<ActionStyle>,
+    },
     /// Defines an `AdaptiveCard` which is shown to the user when the button or link is clicked.
     #[serde(rename = "Action.ShowCard")]
     ShowCard {
@@ -1105,6 +2136,50 @@ pub enum Action {
     },
 }
 
+impl Action {
+    /// Create an `Action.OpenUrl`, checking the URL with [`Action::validate()`]
+    ///
+    /// # Errors
+    /// Returns an error if the URL is invalid or isn't `http` or `https`.
+    pub fn open_url(url: impl Into<String>) -> Result<Self, CardValidationError> {
+        let action = Self::OpenUrl {
+            url: url.into(),
+            title: None,
+            style: None,
+        };
+        action.validate()?;
+        Ok(action)
+    }
+
+    /// Check that the URL of an `Action.OpenUrl` is a valid `http` or `https` URL, and validate
+    /// the card of an `Action.ShowCard`. Clients ignore clicks on other URLs.
+    ///
+    /// # Errors
+    /// Returns the first problem found.
+    pub fn validate(&self) -> Result<(), CardValidationError> {
+        match self {
+            Self::OpenUrl { url, .. } => {
+                let parsed = url::Url::parse(url)
+                    .map_err(|e| CardValidationError::InvalidUrl(url.clone(), e.to_string()))?;
+                match parsed.scheme() {
+                    "http" | "https" => Ok(()),
+                    _ => Err(CardValidationError::UnsupportedScheme(url.clone())),
+                }
+            }
+            Self::ShowCard { card, .. } => card.validate(),
+            Self::Submit { .. } | Self::Execute { .. } => Ok(()),
+        }
+    }
+
+    /// The lowest card version supporting this action
+    const fn min_version(&self) -> CardVersion {
+        match self {
+            Self::Execute { .. } => CardVersion::V14,
+            _ => CardVersion::V11,
+        }
+    }
+}
+
 /// Controls the style of an Action, which influences how the action is displayed, spoken, etc.
 #[allow(missing_docs)]
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
@@ -1127,6 +2202,376 @@ pub struct Choice {
     pub value: String,
 }
 
+impl Choice {
+    /// Create a new choice from a display title and raw value
+    #[must_use]
+    pub fn new<T: Into<String>, S: Into<String>>(title: T, value: S) -> Self {
+        Self {
+            title: title.into(),
+            value: value.into(),
+        }
+    }
+}
+
 fn default_version() -> String {
     "1.1".to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff() {
+        let old = AdaptiveCard::new()
+            .add_body(CardElement::text_block("a"))
+            .add_body(CardElement::text_block("b"))
+            .add_action(Action::Submit {
+                data: None,
+                title: Some("Go".into()),
+                style: None,
+            });
+        assert!(old.diff(&old).is_empty());
+
+        let mut new = old.clone();
+        new.body = Some(vec![CardElement::text_block("c")]);
+        new.fallback_text = Some("fallback".into());
+        new.set_version(CardVersion::V13);
+        assert_eq!(
+            old.diff(&new),
+            [
+                CardDiff::VersionChanged("1.1".into(), "1.3".into()),
+                CardDiff::BodyElementChanged(0, CardElement::text_block("c")),
+                CardDiff::BodyElementRemoved(1),
+                CardDiff::PropertiesChanged,
+            ]
+        );
+    }
+
+    #[test]
+    fn columns_from_elements() {
+        let groups = vec![
+            vec![CardElement::text_block("a"), CardElement::text_block("b")],
+            vec![CardElement::text_block("c")],
+        ];
+        let set = CardElement::from(groups.into_iter().map(Column::from).collect::<Vec<_>>());
+        let collected: Column = ["a", "b"]
+            .into_iter()
+            .map(CardElement::text_block)
+            .collect();
+        let CardElement::ColumnSet { columns, .. } = set else {
+            panic!("expected a ColumnSet");
+        };
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns[0], collected);
+        assert_eq!(columns[1].items.len(), 1);
+        assert_eq!(columns[1].width, None);
+    }
+
+    #[test]
+    fn execute_action_nested_data() {
+        let action: Action = serde_json::from_str(
+            r#"{"type": "Action.Execute", "verb": "approve", "data": {"item": {"id": 42}}}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            action,
+            Action::Execute { data: Some(data), .. } if data["item"]["id"] == 42
+        ));
+    }
+
+    #[test]
+    fn from_template_substitutes_values_verbatim() {
+        let template = r#"{"type": "AdaptiveCard", "version": "1.2",
+            "body": [{"type": "TextBlock", "text": "Hello {{ name }}, {{missing}}"}]}"#;
+        let data = HashMap::from([("name".to_string(), r#"Bob", "x": "<b>"#.to_string())]);
+        let card = AdaptiveCard::from_template(template, &data).unwrap();
+        let body = card.body.unwrap();
+        assert!(matches!(
+            &body[0],
+            CardElement::TextBlock { text, .. } if text == r#"Hello Bob", "x": "<b>, {{missing}}"#
+        ));
+    }
+
+    #[test]
+    fn from_template_rejects_placeholders_outside_strings() {
+        let template = r#"{"type": "AdaptiveCard", "version": "1.2",
+            "body": [{"type": "TextBlock", "text": "hi", "maxLines": {{n}}}]}"#;
+        let data = HashMap::from([("n".to_string(), r#"1, "url": "https://evil""#.to_string())]);
+        assert!(matches!(
+            AdaptiveCard::from_template(template, &data),
+            Err(crate::error::Error::Json(_))
+        ));
+    }
+
+    #[test]
+    fn background_image_from_url_or_object() {
+        let plain: BackgroundImage =
+            serde_json::from_str(r#""https://example.com/a.png""#).unwrap();
+        assert_eq!(plain, BackgroundImage::new("https://example.com/a.png"));
+        let full: BackgroundImage =
+            serde_json::from_str(r#"{"url": "https://example.com/a.png", "fillMode": "repeat"}"#)
+                .unwrap();
+        assert_eq!(full.fill_mode, Some(FillMode::Repeat));
+    }
+
+    #[test]
+    fn set_and_get_id() {
+        assert_eq!(CardElement::container().id(), None);
+        assert_eq!(CardElement::container().set_id("box").id(), Some("box"));
+        assert_eq!(
+            CardElement::input_text("old", None::<String>)
+                .set_id("new")
+                .id(),
+            Some("new")
+        );
+    }
+
+    #[test]
+    fn input_constructors_serialize() {
+        let number =
+            serde_json::to_value(CardElement::input_number("n", Some(1.0), None, Some(2.5)))
+                .unwrap();
+        assert_eq!(
+            number,
+            serde_json::json!({"type": "Input.Number", "id": "n", "min": 1.0, "value": 2.5})
+        );
+        let date =
+            serde_json::to_value(CardElement::input_date("d", None, Some("2024-12-31"), None))
+                .unwrap();
+        assert_eq!(
+            date,
+            serde_json::json!({"type": "Input.Date", "id": "d", "max": "2024-12-31"})
+        );
+        let time =
+            serde_json::to_value(CardElement::input_time("t", Some("09:00"), None, None)).unwrap();
+        assert_eq!(
+            time,
+            serde_json::json!({"type": "Input.Time", "id": "t", "min": "09:00"})
+        );
+    }
+
+    #[test]
+    fn facts_from_iterator() {
+        let pairs = [("Status", "Open"), ("Owner", "alice")];
+        let set = CardElement::fact_set()
+            .set_facts(pairs.iter().map(|(k, v)| Fact::new(*k, *v)).collect());
+        assert_eq!(
+            set,
+            CardElement::fact_set()
+                .add_key_value("Status", "Open")
+                .add_key_value("Owner", "alice")
+        );
+    }
+
+    #[test]
+    fn set_version() {
+        assert_eq!(AdaptiveCard::new().version, "1.1");
+        assert_eq!(
+            AdaptiveCard::new().set_version(CardVersion::V13).version,
+            "1.3"
+        );
+        assert_eq!(AdaptiveCard::new().set_version("1.2").version, "1.2");
+    }
+
+    #[test]
+    fn text_block_horizontal_alignment_key() {
+        let block = CardElement::text_block("centered")
+            .set_horizontal_alignment(HorizontalAlignment::Center);
+        let json = serde_json::to_value(&block).unwrap();
+        assert_eq!(json["horizontalAlignment"], "Center");
+        assert!(json.get("HorizontalAlignment").is_none());
+        assert_eq!(serde_json::from_value::<CardElement>(json).unwrap(), block);
+    }
+
+    #[test]
+    fn schema_is_not_allocated() {
+        let card = AdaptiveCard::new();
+        assert!(matches!(
+            card.schema,
+            Some(Cow::Borrowed(ADAPTIVE_CARD_SCHEMA))
+        ));
+        let json = serde_json::to_value(&card).unwrap();
+        assert_eq!(json["$schema"], ADAPTIVE_CARD_SCHEMA);
+        assert_eq!(serde_json::from_value::<AdaptiveCard>(json).unwrap(), card);
+    }
+
+    #[test]
+    fn set_horizontal_alignment() {
+        for element in [
+            CardElement::image("https://example.com/a.png"),
+            CardElement::column_set(),
+        ] {
+            let json = serde_json::to_value(
+                element
+                    .clone()
+                    .set_horizontal_alignment(HorizontalAlignment::Right),
+            )
+            .unwrap();
+            assert_eq!(json["horizontalAlignment"], "Right");
+        }
+        let container = CardElement::container();
+        assert_eq!(
+            container
+                .clone()
+                .set_horizontal_alignment(HorizontalAlignment::Right),
+            container
+        );
+    }
+
+    #[test]
+    fn set_input_values() {
+        let time = CardElement::input_time("t", None::<&str>, None, None).set_value_str("09:30");
+        assert_eq!(serde_json::to_value(time).unwrap()["value"], "09:30");
+        let number = CardElement::input_number("n", None, None, None).set_value_num(2.5);
+        assert_eq!(serde_json::to_value(&number).unwrap()["value"], 2.5);
+        // Wrong value type for the element: unchanged
+        assert_eq!(number.clone().set_value_str("x"), number);
+        let text = CardElement::input_text("s", None::<&str>);
+        assert_eq!(text.clone().set_value_num(1.0), text);
+    }
+
+    #[test]
+    fn validate_open_url() {
+        assert!(Action::open_url("https://example.com/a?b=c").is_ok());
+        assert!(matches!(
+            Action::open_url("not a url"),
+            Err(CardValidationError::InvalidUrl(..))
+        ));
+        let script = "javascript:alert(1)";
+        // Nested in a column of the body of a card shown by an action
+        let inner = AdaptiveCard::new().add_body(CardElement::from(vec![Column::new()
+            .add_element(
+                CardElement::action_set().add_action_to_set(Action::OpenUrl {
+                    url: script.to_string(),
+                    title: None,
+                    style: None,
+                }),
+            )]));
+        let card = AdaptiveCard::new().add_action(Action::ShowCard {
+            card: inner,
+            title: None,
+            style: None,
+        });
+        assert_eq!(
+            card.validate(),
+            Err(CardValidationError::UnsupportedScheme(script.to_string()))
+        );
+        assert_eq!(AdaptiveCard::new().validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_version_of_required_inputs() {
+        let input =
+            CardElement::input_text("name", None::<String>).set_required(true, "Name is required");
+        let mut card = AdaptiveCard::new().add_body(CardElement::container().add_element(input));
+        assert_eq!(
+            card.validate(),
+            Err(CardValidationError::VersionTooLow(
+                "1.1".to_string(),
+                "1.3".to_string()
+            ))
+        );
+        assert_eq!(card.set_version(CardVersion::V13).validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_version_of_labels() {
+        let input = CardElement::input_number("count", None, None, None).set_label("Count");
+        let mut card = AdaptiveCard::new_with_version(CardVersion::V12).add_body(input);
+        assert_eq!(
+            card.validate(),
+            Err(CardValidationError::VersionTooLow(
+                "1.2".to_string(),
+                "1.3".to_string()
+            ))
+        );
+        assert_eq!(card.set_version(CardVersion::V13).validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_version_of_action_set() {
+        let mut card = AdaptiveCard::new().add_body(CardElement::action_set());
+        assert_eq!(
+            card.validate(),
+            Err(CardValidationError::VersionTooLow(
+                "1.1".to_string(),
+                "1.2".to_string()
+            ))
+        );
+        assert_eq!(card.set_version(CardVersion::V12).validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_version_of_execute() {
+        let execute = Action::Execute {
+            verb: Some("approve".to_string()),
+            data: None,
+            title: None,
+            style: None,
+        };
+        let mut card = AdaptiveCard::new_with_version(CardVersion::V13).add_body(
+            CardElement::container()
+                .add_element(CardElement::action_set().add_action_to_set(execute)),
+        );
+        assert_eq!(
+            card.validate(),
+            Err(CardValidationError::VersionTooLow(
+                "1.3".to_string(),
+                "1.4".to_string()
+            ))
+        );
+        assert_eq!(card.set_version(CardVersion::V14).validate(), Ok(()));
+    }
+
+    #[test]
+    fn set_style() {
+        let image = CardElement::image("https://example.com/a.png").set_style(ImageStyle::Person);
+        assert!(matches!(
+            image,
+            CardElement::Image {
+                style: Some(ImageStyle::Person),
+                ..
+            }
+        ));
+        let input = CardElement::input_text("a", None::<String>).set_style(TextInputStyle::Email);
+        assert!(matches!(
+            input,
+            CardElement::InputText {
+                style: Some(TextInputStyle::Email),
+                ..
+            }
+        ));
+        let set = CardElement::column_set().set_container_style(ContainerStyle::Good);
+        assert!(matches!(
+            set,
+            CardElement::ColumnSet {
+                style: Some(ContainerStyle::Good),
+                ..
+            }
+        ));
+        // Unsupported combinations are left unchanged
+        let text = CardElement::text_block("a");
+        assert_eq!(text.clone().set_style(ContainerStyle::Good), text);
+        assert_eq!(image.clone().set_style(ContainerStyle::Good), image);
+    }
+
+    #[test]
+    fn image_set() {
+        let set = CardElement::image_set()
+            .add_image_to_set("https://example.com/a.png")
+            .add_image_to_set("https://example.com/b.png");
+        assert_eq!(set.images().map(<[_]>::len), Some(2));
+        assert_eq!(CardElement::container().images(), None);
+        assert_eq!(
+            serde_json::to_value(&set).unwrap(),
+            serde_json::json!({
+                "type": "ImageSet",
+                "images": [
+                    {"type": "Image", "url": "https://example.com/a.png"},
+                    {"type": "Image", "url": "https://example.com/b.png"}
+                ]
+            })
+        );
+    }
+}
@@ -2,8 +2,9 @@
 //! Ways to authenticate with the Webex API
 
 use crate::{AuthorizationType, RestClient};
+use base64::Engine;
 use reqwest::StatusCode;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tokio::time::{self, Duration, Instant};
 
 const SCOPE: &str = "spark:all";
@@ -17,6 +18,7 @@ const GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
 pub struct DeviceAuthenticator {
     client_id: String,
     client_secret: String,
+    scope: String,
     client: RestClient,
 }
 
@@ -38,6 +40,15 @@ pub struct VerificationToken {
     interval: u64,
 }
 
+impl VerificationToken {
+    /// Minimum time to wait between two polls of
+    /// [`poll_once`](DeviceAuthenticator::poll_once).
+    #[must_use]
+    pub const fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval)
+    }
+}
+
 #[derive(Deserialize, Debug)]
 struct TokenResponse {
     access_token: String,
@@ -57,15 +68,31 @@ impl DeviceAuthenticator {
         Self {
             client_id: id.to_string(),
             client_secret: secret.to_string(),
+            scope: SCOPE.to_string(),
             client,
         }
     }
 
+    /// Request only the given scopes (e.g. `["spark:messages_read", "spark:messages_write"]`)
+    /// instead of all of them (`"spark:all"`). The scopes have to be enabled for the
+    /// integration.
+    #[must_use]
+    pub fn with_scopes(mut self, scopes: &[&str]) -> Self {
+        self.scope = scopes.join(" ");
+        self
+    }
+
     /// First step of device authentication. Returns a [`VerificationToken`]
     /// containing the codes and URLs that can be entered and navigated to
     /// on a different device.
+    ///
+    /// Requests the scopes set with [`with_scopes`](DeviceAuthenticator::with_scopes), or
+    /// `"spark:all"` by default.
     pub async fn verify(&self) -> Result<VerificationToken, crate::Error> {
-        let params = &[("client_id", self.client_id.as_str()), ("scope", SCOPE)];
+        let params = &[
+            ("client_id", self.client_id.as_str()),
+            ("scope", self.scope.as_str()),
+        ];
         let verification_token = self
             .client
             .api_post_form_urlencoded::<VerificationToken>(
@@ -81,49 +108,206 @@ impl DeviceAuthenticator {
     /// Second and final step of device authentication. Receives a [`VerificationToken`]
     /// provided by [`verify`](DeviceAuthenticator::verify) and blocks until the user enters their crendentials using
     /// the provided codes/links from [`VerificationToken`]. Returns a [`Bearer`] if successful.
+    ///
+    /// To show progress or give up early, call [`poll_once`](DeviceAuthenticator::poll_once) in
+    /// your own loop instead.
     pub async fn wait_for_authentication(
         &self,
         verification_token: &VerificationToken,
     ) -> Result<Bearer, crate::Error> {
+        let mut interval = time::interval_at(
+            Instant::now() + verification_token.interval(),
+            verification_token.interval() + Duration::from_secs(1),
+        );
+
+        loop {
+            interval.tick().await;
+
+            if let Some(bearer) = self.poll_once(verification_token).await? {
+                return Ok(bearer);
+            }
+        }
+    }
+
+    /// Checks once whether the user has completed authentication. Returns `Ok(None)` while
+    /// authentication is still pending, and a [`Bearer`] once it has succeeded.
+    ///
+    /// Callers should wait at least [`VerificationToken::interval()`] between calls.
+    pub async fn poll_once(
+        &self,
+        verification_token: &VerificationToken,
+    ) -> Result<Option<Bearer>, crate::Error> {
         let params = [
             ("grant_type", GRANT_TYPE),
             ("device_code", &verification_token.device_code),
             ("client_id", &self.client_id),
         ];
 
-        let mut interval = time::interval_at(
-            Instant::now() + Duration::from_secs(verification_token.interval),
-            Duration::from_secs(verification_token.interval + 1),
-        );
+        match self
+            .client
+            .api_post_form_urlencoded::<TokenResponse>(
+                "device/token",
+                params,
+                None::<()>,
+                AuthorizationType::Basic {
+                    username: &self.client_id,
+                    password: &self.client_secret,
+                },
+            )
+            .await
+        {
+            Ok(token) => Ok(Some(token.access_token)),
+            Err(
+                crate::error::Error::StatusText(StatusCode::PRECONDITION_REQUIRED, _)
+                | crate::error::Error::Status(StatusCode::PRECONDITION_REQUIRED),
+            ) => Ok(None),
+            Err(_) => Err(crate::Error::Authentication),
+        }
+    }
+}
 
-        loop {
-            interval.tick().await;
+/// Creates guest tokens (JWTs) for users without a Webex account, signed with the secret of a
+/// Webex Guest Issuer app.
+///
+/// The token can be exchanged for an access token with
+/// [`Webex::new_as_guest()`](crate::Webex::new_as_guest).
+///
+/// More information can be found on <https://developer.webex.com/docs/guest-issuer>.
+#[must_use]
+pub struct GuestTokenBuilder {
+    issuer: String,
+    secret: String,
+    subject: Option<String>,
+    name: Option<String>,
+    expiry: Duration,
+}
 
-            match self
-                .client
-                .api_post_form_urlencoded::<TokenResponse>(
-                    "device/token",
-                    params,
-                    None::<()>,
-                    AuthorizationType::Basic {
-                        username: &self.client_id,
-                        password: &self.client_secret,
-                    },
-                )
-                .await
-            {
-                Ok(token) => return Ok(token.access_token),
-                Err(e) => match e {
-                    crate::error::Error::StatusText(http_status, _) => {
-                        if http_status != StatusCode::PRECONDITION_REQUIRED {
-                            return Err(crate::Error::Authentication);
-                        }
-                    }
-                    _ => {
-                        return Err(crate::Error::Authentication);
-                    }
-                },
-            }
+#[derive(Serialize)]
+struct GuestClaims<'a> {
+    sub: &'a str,
+    name: &'a str,
+    iss: &'a str,
+    exp: u64,
+}
+
+impl GuestTokenBuilder {
+    /// Creates a new builder from the "guest issuer ID" and base64 encoded "shared secret" of a
+    /// Guest Issuer app. Tokens expire after one hour unless set otherwise.
+    #[allow(clippy::duration_suboptimal_units)]
+    pub fn new(issuer: &str, secret: &str) -> Self {
+        Self {
+            issuer: issuer.to_string(),
+            secret: secret.to_string(),
+            subject: None,
+            name: None,
+            expiry: Duration::from_secs(60 * 60),
         }
     }
+
+    /// Sets the display name of the guest user. Required.
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Sets the unique, stable identifier of the guest user in your application. Required.
+    pub fn with_subject(mut self, subject: &str) -> Self {
+        self.subject = Some(subject.to_string());
+        self
+    }
+
+    /// Sets for how long the token is valid, from the time it is built.
+    pub const fn with_expiry(mut self, expiry: Duration) -> Self {
+        self.expiry = expiry;
+        self
+    }
+
+    /// Builds and signs the guest token.
+    ///
+    /// # Errors
+    /// * [`Error::Other`](crate::Error::Other) - returned when the name or subject are not set.
+    /// * [`Error::Wrapped`](crate::Error::Wrapped) - returned when the secret is not valid base64.
+    /// * [`Error::Jwt`](crate::Error::Jwt) - returned when the token cannot be signed.
+    pub fn build(&self) -> Result<String, crate::Error> {
+        let (Some(sub), Some(name)) = (&self.subject, &self.name) else {
+            return Err("guest token requires a subject and a name".into());
+        };
+        let secret = base64::engine::general_purpose::STANDARD
+            .decode(&self.secret)
+            .map_err(|e| crate::Error::wrap(e, "invalid guest issuer secret"))?;
+        let exp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .saturating_add(self.expiry)
+            .as_secs();
+        let claims = GuestClaims {
+            sub,
+            name,
+            iss: &self.issuer,
+            exp,
+        };
+        Ok(jsonwebtoken::encode(
+            &jsonwebtoken::Header::default(),
+            &claims,
+            &jsonwebtoken::EncodingKey::from_secret(&secret),
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn verify_requests_scopes() {
+        use wiremock::{
+            matchers::{body_string_contains, method, path},
+            Mock, MockServer, ResponseTemplate,
+        };
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/device/authorize"))
+            .and(body_string_contains(
+                "scope=spark%3Amessages_read+spark%3Amessages_write",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "user_code": "123",
+                "device_code": "abc",
+                "verification_uri": "https://example.com",
+                "verification_uri_complete": "https://example.com?code=123",
+                "interval": 2
+            })))
+            .mount(&server)
+            .await;
+
+        let mut authenticator = DeviceAuthenticator::new("id", "secret")
+            .with_scopes(&["spark:messages_read", "spark:messages_write"]);
+        authenticator.client.default_prefix = server.uri();
+        let token = authenticator.verify().await.unwrap();
+        assert_eq!(token.interval(), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn guest_token() {
+        let secret = base64::engine::general_purpose::STANDARD.encode("not so secret");
+        assert!(GuestTokenBuilder::new("issuer", &secret).build().is_err());
+
+        let jwt = GuestTokenBuilder::new("issuer", &secret)
+            .with_subject("guest-1")
+            .with_name("Guest")
+            .build()
+            .unwrap();
+        let mut validation = jsonwebtoken::Validation::default();
+        validation.set_issuer(&["issuer"]);
+        let claims = jsonwebtoken::decode::<serde_json::Value>(
+            &jwt,
+            &jsonwebtoken::DecodingKey::from_secret(b"not so secret"),
+            &validation,
+        )
+        .unwrap()
+        .claims;
+        assert_eq!(claims["sub"], "guest-1");
+        assert_eq!(claims["name"], "Guest");
+    }
 }
@@ -0,0 +1,258 @@
+#![deny(missing_docs)]
+//! Helpers for writing command-driven bots
+//!
+//! A [`CommandRouter`] dispatches posted messages to handlers based on their text, e.g.:
+//!
+//! ```no_run
+//! # async fn run(webex: webex::Webex) -> Result<(), webex::error::Error> {
+//! use webex::bot::CommandRouter;
+//!
+//! let router = CommandRouter::new()
+//!     .on("ping", |webex, msg| {
+//!         Box::pin(async move {
+//!             let mut reply = webex::MessageOut::from(msg);
+//!             reply.text = Some("pong".to_string());
+//!             webex.send_message(&reply).await.map(|_| ())
+//!         })
+//!     })
+//!     .fallback(|_, _| Box::pin(async { Ok(()) }));
+//!
+//! let mut events = webex.event_stream().await?;
+//! while let Ok(event) = events.next().await {
+//!     router.handle(&webex, &event).await?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{error::Error, ActivityType, Event, Message, MessageActivity, Webex};
+use futures::future::BoxFuture;
+use regex::Regex;
+
+/// Command handler, called with the client and the message that matched
+type Handler =
+    Box<dyn for<'a> Fn(&'a Webex, &'a Message) -> BoxFuture<'a, Result<(), Error>> + Send + Sync>;
+
+enum Pattern {
+    Command(String),
+    Regex(Regex),
+}
+
+impl Pattern {
+    fn matches(&self, text: &str) -> bool {
+        match self {
+            Self::Command(command) => text
+                .split_whitespace()
+                .next()
+                .is_some_and(|word| word.eq_ignore_ascii_case(command)),
+            Self::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+/// Routes posted messages to the first handler whose pattern matches the message text.
+///
+/// The mention of the bot that starts messages in group rooms is stripped before matching, see
+/// [`command_text()`].
+#[derive(Default)]
+#[must_use]
+pub struct CommandRouter {
+    routes: Vec<(Pattern, Handler)>,
+    fallback: Option<Handler>,
+}
+
+impl CommandRouter {
+    /// Creates a router without any routes
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Routes messages whose first word is `command` (ignoring ASCII case) to `handler`
+    pub fn on<F>(mut self, command: impl Into<String>, handler: F) -> Self
+    where
+        F: for<'a> Fn(&'a Webex, &'a Message) -> BoxFuture<'a, Result<(), Error>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.routes
+            .push((Pattern::Command(command.into()), Box::new(handler)));
+        self
+    }
+
+    /// Routes messages matching `regex` to `handler`
+    pub fn on_regex<F>(mut self, regex: Regex, handler: F) -> Self
+    where
+        F: for<'a> Fn(&'a Webex, &'a Message) -> BoxFuture<'a, Result<(), Error>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.routes.push((Pattern::Regex(regex), Box::new(handler)));
+        self
+    }
+
+    /// Routes messages that match no other route to `handler`. Without a fallback, unmatched
+    /// messages are ignored.
+    pub fn fallback<F>(mut self, handler: F) -> Self
+    where
+        F: for<'a> Fn(&'a Webex, &'a Message) -> BoxFuture<'a, Result<(), Error>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.fallback = Some(Box::new(handler));
+        self
+    }
+
+    /// Handles an event from the event stream. Events other than posted messages are ignored,
+    /// as are the bot's own messages (see [`Webex::event_is_mine()`]), so that replies don't
+    /// trigger more replies. For other posted messages, the message is fetched and passed to
+    /// [`Self::dispatch()`].
+    ///
+    /// # Errors
+    /// Returns errors from fetching the bot's details or the message, or from the handler.
+    pub async fn handle(&self, webex: &Webex, event: &Event) -> Result<(), Error> {
+        if event.activity_type() != ActivityType::Message(MessageActivity::Posted)
+            || webex.event_is_mine(event).await?
+        {
+            return Ok(());
+        }
+        let message: Message = webex.get(&event.try_global_id()?).await?;
+        self.dispatch(webex, &message).await
+    }
+
+    /// Passes a message to the first matching handler, or to the fallback.
+    ///
+    /// # Errors
+    /// Returns errors from the handler.
+    pub async fn dispatch(&self, webex: &Webex, message: &Message) -> Result<(), Error> {
+        let text = command_text(message);
+        let handler = self
+            .routes
+            .iter()
+            .find(|(pattern, _)| pattern.matches(text))
+            .map(|(_, handler)| handler)
+            .or(self.fallback.as_ref());
+        match handler {
+            Some(handler) => handler(webex, message).await,
+            None => Ok(()),
+        }
+    }
+}
+
+/// Get the text of a message, without the mention of the bot that starts messages sent to a bot
+/// in group rooms (e.g. "`BotName help`" becomes "`help`").
+#[must_use]
+pub fn command_text(message: &Message) -> &str {
+    let text = message.text.as_deref().unwrap_or_default().trim();
+    leading_mention(message.html.as_deref().unwrap_or_default())
+        .and_then(|mention| text.strip_prefix(mention))
+        .map_or(text, str::trim_start)
+}
+
+/// Get the display text of the mention that starts an HTML message, if any
+fn leading_mention(html: &str) -> Option<&str> {
+    let html = html.trim_start();
+    let html = html.strip_prefix("<p>").unwrap_or(html).trim_start();
+    let mention = html.strip_prefix("<spark-mention")?;
+    let mention = &mention[mention.find('>')? + 1..];
+    Some(&mention[..mention.find("</spark-mention>")?])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    fn message(text: &str, html: Option<&str>) -> Message {
+        Message {
+            text: Some(text.to_string()),
+            html: html.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn strips_leading_mention() {
+        let group = message(
+            "Bot Name help me",
+            Some(
+                r#"<p><spark-mention data-object-type="person" data-object-id="abc">Bot Name</spark-mention> help me</p>"#,
+            ),
+        );
+        assert_eq!(command_text(&group), "help me");
+        assert_eq!(command_text(&message("help me", None)), "help me");
+        let mention_later = message(
+            "ask Alice",
+            Some(r#"<p>ask <spark-mention data-object-type="person">Alice</spark-mention></p>"#),
+        );
+        assert_eq!(command_text(&mention_later), "ask Alice");
+    }
+
+    #[tokio::test]
+    async fn dispatches_to_first_match() {
+        fn record(
+            calls: &Arc<AtomicUsize>,
+            n: usize,
+        ) -> impl for<'a> Fn(&'a Webex, &'a Message) -> BoxFuture<'a, Result<(), Error>>
+               + Send
+               + Sync
+               + 'static {
+            let calls = Arc::clone(calls);
+            move |_, _| {
+                calls.store(n, Ordering::SeqCst);
+                Box::pin(async { Ok(()) })
+            }
+        }
+        let calls = Arc::new(AtomicUsize::new(0));
+        let router = CommandRouter::new()
+            .on("help", record(&calls, 1))
+            .on_regex(Regex::new(r"^deploy \w+$").unwrap(), record(&calls, 2))
+            .on("HELP", record(&calls, 3))
+            .fallback(record(&calls, 4));
+        let webex = crate::tests::offline_webex();
+
+        for (text, expected) in [
+            ("Help", 1),
+            ("deploy prod", 2),
+            ("deploy prod now", 4),
+            ("", 4),
+        ] {
+            router.dispatch(&webex, &message(text, None)).await.unwrap();
+            assert_eq!(calls.load(Ordering::SeqCst), expected, "{text}");
+        }
+    }
+
+    #[tokio::test]
+    async fn ignores_own_messages() {
+        let mock = crate::testing::MockWebex::start().await;
+        mock.stub_json("GET", "/people/me", &serde_json::json!({ "id": "bot" }))
+            .await;
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let router = CommandRouter::new().fallback(move |_, _| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async { Ok(()) })
+        });
+        let mut activity = crate::Activity {
+            verb: "post".to_string(),
+            ..Default::default()
+        };
+        activity.actor.id = "bot".to_string();
+        let event = Event {
+            data: crate::EventData {
+                event_type: "conversation.activity".to_string(),
+                activity: Some(activity),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        router.handle(&mock.webex(), &event).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+}
@@ -12,6 +12,9 @@ pub enum Error {
     #[error("UTF8 error: {0}")]
     UTF8(#[from] std::str::Utf8Error),
 
+    #[error("JWT error: {0}")]
+    Jwt(#[from] jsonwebtoken::errors::Error),
+
     #[error("reqwest error: {0}")]
     Reqwest(#[from] reqwest::Error),
 
@@ -24,8 +27,8 @@ pub enum Error {
     StatusText(StatusCode, String),
     #[error("{0} Retry in: '{1:?}'")]
     Limited(StatusCode, Option<i64>),
-    #[error("{0} {1}")]
-    Tungstenite(tokio_tungstenite::tungstenite::Error, String),
+    #[error("{1}: {0}")]
+    Tungstenite(#[source] tokio_tungstenite::tungstenite::Error, String),
     #[error("Webex API changed: {0}")]
     Api(&'static str),
 
@@ -35,6 +38,21 @@ pub enum Error {
     // catch-all
     #[error("Unknown error: {0}")]
     Other(String),
+    #[error("{message}: {source}")]
+    Wrapped {
+        message: String,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+impl Error {
+    /// Wrap an error with a message describing what failed, keeping it as the source
+    pub fn wrap(e: impl std::error::Error + Send + Sync + 'static, msg: impl Into<String>) -> Self {
+        Error::Wrapped {
+            message: msg.into(),
+            source: Box::new(e),
+        }
+    }
 }
 
 impl From<String> for Error {
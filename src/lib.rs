@@ -7,6 +7,8 @@
 #![allow(clippy::future_not_send)]
 #![allow(clippy::missing_errors_doc)]
 #![allow(clippy::option_if_let_else)]
+// `Error` carries a tungstenite error, which is large - boxing it would be an API break
+#![allow(clippy::result_large_err)]
 #![cfg_attr(test, deny(warnings))]
 #![doc(html_root_url = "https://docs.rs/webex/latest/webex/")]
 
@@ -22,6 +24,7 @@
 //! - Sending direct or group messages
 //! - Getting room memberships
 //! - Building `AdaptiveCards` and retrieving responses
+//! - Routing bot commands to handlers
 //!
 //! Not all features are fully-fleshed out, particularly the `AdaptiveCard`
 //! support (only a few serializations exist, enough to create a form with a
@@ -41,22 +44,28 @@ pub mod error;
 pub mod types;
 pub use types::*;
 pub mod auth;
+pub mod bot;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
 use error::Error;
 
 use crate::adaptive_card::AdaptiveCard;
-use futures::{future::try_join_all, try_join};
+use bytes::Bytes;
+use futures::{future::try_join_all, ready, try_join, Stream};
 use futures_util::{SinkExt, StreamExt};
 use log::{debug, error, trace, warn};
 use reqwest::StatusCode;
 use serde::{de::DeserializeOwned, Serialize};
 use std::{
-    collections::{hash_map::DefaultHasher, HashMap},
-    hash::{self, Hasher},
-    sync::Mutex,
-    time::Duration,
+    collections::{HashMap, HashSet, VecDeque},
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
 };
-use tokio::net::TcpStream;
+use tokio::{net::TcpStream, sync::OnceCell};
 use tokio_tungstenite::{
     connect_async,
     tungstenite::{Error as TErr, Message as TMessage},
@@ -74,6 +83,8 @@ use tokio_tungstenite::{
 
 // Main API URL - default for any request.
 const REST_HOST_PREFIX: &str = "https://api.ciscospark.com/v1";
+// Hosts of the main API, as found in absolute URLs returned by it (file URLs, pagination links).
+const REST_HOSTS: [&str; 2] = ["webexapis.com", "api.ciscospark.com"];
 // U2C - service discovery, used to discover other URLs (for example, the mercury URL).
 const U2C_HOST_PREFIX: &str = "https://u2c.wbx2.com/u2c/api/v1";
 // Default mercury URL, used when the token doesn't have permissions to list organizations.
@@ -85,6 +96,24 @@ const CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
 const DEFAULT_DEVICE_NAME: &str = "rust-client";
 const DEVICE_SYSTEM_NAME: &str = "rust-spark-client";
 
+// Time without any message (including pings) after which the event stream is considered dead
+const DEFAULT_EVENT_STREAM_TIMEOUT: Duration = Duration::from_secs(20);
+
+// Existing devices to try connecting to before registering a new one
+const DEFAULT_MAX_DEVICE_ATTEMPTS: usize = 3;
+
+// Time to wait for the reply to the authentication message of an event stream
+const DEFAULT_AUTH_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Mercury URLs by token ID, or () if fetching failed. error::Error does not implement clone.
+#[allow(clippy::non_std_lazy_statics)]
+fn mercury_cache() -> &'static Mutex<HashMap<u64, Result<String, ()>>> {
+    lazy_static::lazy_static! {
+        static ref MERCURY_CACHE: Mutex<HashMap<u64, Result<String, ()>>> = Mutex::new(HashMap::new());
+    }
+    &MERCURY_CACHE
+}
+
 /// Web Socket Stream type
 pub type WStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
@@ -97,25 +126,162 @@ pub struct Webex {
     token: String,
     /// Webex Device Information used for device registration
     pub device: DeviceData,
+    max_device_attempts: usize,
+    auth_timeout: Duration,
+    /// The authenticated user, fetched on first use
+    me: Arc<OnceCell<Person>>,
+}
+
+/// Builder for a [`Webex`] client, for when [`Webex::new()`] isn't flexible enough
+#[must_use]
+pub struct WebexBuilder {
+    token: String,
+    device_name: String,
+    proxy: Option<reqwest::Proxy>,
+}
+
+impl WebexBuilder {
+    /// Creates a new builder from a token
+    pub fn new(token: &str) -> Self {
+        Self {
+            token: token.to_string(),
+            device_name: DEFAULT_DEVICE_NAME.to_string(),
+            proxy: None,
+        }
+    }
+
+    /// Sets the name used to identify the device/client with Webex api
+    pub fn device_name(mut self, device_name: &str) -> Self {
+        self.device_name = device_name.to_string();
+        self
+    }
+
+    /// Sends all REST requests through a proxy. The event stream websocket is not proxied.
+    /// Hosts that should bypass the proxy can be set with [`reqwest::Proxy::no_proxy()`].
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Builds the [`Webex`] client
+    pub async fn build(self) -> Result<Webex, Error> {
+        let client = match self.proxy {
+            Some(proxy) => RestClient::with_proxy(proxy)?,
+            None => RestClient::new(),
+        };
+        Ok(Webex::new_with_client(client, &self.device_name, &self.token).await)
+    }
+}
+
+/// Stable identifier of a token, to key caches without keeping the token itself.
+///
+/// Uses 64-bit FNV-1a, as the output of `DefaultHasher` may change between Rust versions.
+fn token_id(token: &str) -> u64 {
+    token.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+fn env_var_error(var: &str, e: std::env::VarError) -> Error {
+    let message = match e {
+        std::env::VarError::NotPresent => format!("{var} not set"),
+        std::env::VarError::NotUnicode(_) => format!("{var} is not valid Unicode"),
+    };
+    Error::wrap(e, message)
 }
 
 /// Webex Event Stream handler
 pub struct WebexEventStream {
     ws_stream: WStream,
     timeout: Duration,
+    stats: EventStreamStats,
+    last_parse_failure: Option<String>,
     /// Signifies if `WebStream` is Open
     pub is_open: bool,
 }
 
+/// Statistics about a [`WebexEventStream`], see [`WebexEventStream::stats()`]
+#[derive(Clone, Copy, Debug)]
+pub struct EventStreamStats {
+    /// Number of events received
+    pub messages_received: u64,
+    /// Number of non-fatal errors encountered
+    pub errors_encountered: u64,
+    /// When the stream was connected
+    pub connected_at: Instant,
+    /// When the last event was received
+    pub last_event_at: Option<Instant>,
+}
+
+impl EventStreamStats {
+    fn new() -> Self {
+        Self {
+            messages_received: 0,
+            errors_encountered: 0,
+            connected_at: Instant::now(),
+            last_event_at: None,
+        }
+    }
+}
+
 impl WebexEventStream {
+    /// Sets how long to wait for any activity (including the server's pings) before considering
+    /// the connection dead and closing the stream. Defaults to 20 seconds.
+    ///
+    /// Very short timeouts cause spurious reconnections on slow networks, while very long ones
+    /// delay the detection of dead connections.
+    #[must_use]
+    pub const fn set_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Get the inactivity timeout of this stream, see [`Self::set_timeout()`]
+    #[must_use]
+    pub const fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Get statistics about the events received on this stream
+    #[must_use]
+    pub const fn stats(&self) -> &EventStreamStats {
+        &self.stats
+    }
+
+    /// Get the raw JSON of the last message that could not be deserialized into an [`Event`].
+    ///
+    /// Useful for forwarding unexpected payloads (e.g. new fields or event types added by the
+    /// API) to a monitoring system.
+    #[must_use]
+    pub fn last_parse_failure(&self) -> Option<&str> {
+        self.last_parse_failure.as_deref()
+    }
+
+    /// Wrap this stream to skip events with a sequence number that was already seen among the
+    /// last `capacity` events. Webex may deliver some events twice around reconnections.
+    #[must_use]
+    pub fn with_deduplication(self, capacity: usize) -> DeduplicatingEventStream {
+        DeduplicatingEventStream {
+            inner: self,
+            capacity,
+            seen: HashSet::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+        }
+    }
+
     /// Get the next event from an event stream
     ///
     /// Returns an event or an error
     ///
     /// # Errors
-    /// Returns an error when the underlying stream has a problem, but will
-    /// continue to work on subsequent calls to `next()` - the errors can safely
-    /// be ignored.
+    /// Returns an error when the underlying stream has a problem. Check [`Self::is_open`] to
+    /// tell fatal errors from transient ones:
+    /// * Fatal: the connection timed out or was closed, or a protocol or IO error occurred.
+    ///   `is_open` is set to `false`, and a new stream has to be opened with
+    ///   [`Webex::event_stream()`].
+    /// * Transient: an event could not be parsed, or a message was too large
+    ///   (`TErr::Capacity`) and was dropped. The stream will continue to work on subsequent
+    ///   calls to `next()` - the errors can safely be ignored.
     pub async fn next(&mut self) -> Result<Event, Error> {
         loop {
             let next = self.ws_stream.next();
@@ -131,7 +297,12 @@ impl WebexEventStream {
                 }
                 // Didn't time out
                 Ok(next_result) => match next_result {
-                    None => continue,
+                    // The underlying stream has ended (tokio-tungstenite reports a closed
+                    // connection this way) and will never yield anything again.
+                    None => {
+                        self.is_open = false;
+                        return Err(Error::Closed("Web Socket Closed".to_string()));
+                    }
                     Some(msg) => match msg {
                         Ok(msg) => {
                             if let Some(h_msg) = self.handle_message(msg)? {
@@ -139,15 +310,31 @@ impl WebexEventStream {
                             }
                             // `None` messages still reset the timeout (e.g. Ping to keep alive)
                         }
-                        Err(TErr::Protocol(_) | TErr::Io(_)) => {
+                        Err(
+                            e @ (TErr::Protocol(_)
+                            | TErr::Io(_)
+                            | TErr::AlreadyClosed
+                            | TErr::ConnectionClosed),
+                        ) => {
                             // Protocol error probably requires a connection reset
                             // IO error is (apart from WouldBlock) generally an error with the
                             // underlying connection and also fatal
+                            // A closed connection cannot be read from again
                             self.is_open = false;
-                            return Err(msg.unwrap_err().to_string().into());
+                            return Err(Error::Tungstenite(e, "Event stream failed".to_string()));
+                        }
+                        Err(TErr::Capacity(e)) => {
+                            // The message was too large and has been dropped, but the
+                            // connection itself is fine
+                            warn!("WebSocket capacity exceeded: {e}");
+                            self.stats.errors_encountered += 1;
+                            return Err(Error::Other(
+                                "WebSocket capacity exceeded, dropping message".to_string(),
+                            ));
                         }
                         Err(e) => {
-                            return Err(Error::Tungstenite(e, "Error getting next_result".into()))
+                            self.stats.errors_encountered += 1;
+                            return Err(Error::Tungstenite(e, "Error getting next_result".into()));
                         }
                     },
                 },
@@ -160,15 +347,25 @@ impl WebexEventStream {
             TMessage::Binary(bytes) => {
                 let json = std::str::from_utf8(&bytes)?;
                 match serde_json::from_str(json) {
-                    Ok(ev) => Ok(Some(ev)),
+                    Ok(ev) => {
+                        self.stats.messages_received += 1;
+                        self.stats.last_event_at = Some(Instant::now());
+                        Ok(Some(ev))
+                    }
                     Err(e) => {
                         warn!("Couldn't deserialize: {:?}.  Original JSON:\n{}", e, &json);
+                        debug!(
+                            "Unknown event format ({e}), please report it at \
+                             https://github.com/wr-org/webex-rust/issues: {json}"
+                        );
+                        self.stats.errors_encountered += 1;
+                        self.last_parse_failure = Some(json.to_string());
                         Err(e.into())
                     }
                 }
             }
             TMessage::Text(t) => {
-                debug!("text: {}", t);
+                debug!("text: {t}");
                 Ok(None)
             }
             TMessage::Ping(_) => {
@@ -176,7 +373,7 @@ impl WebexEventStream {
                 Ok(None)
             }
             TMessage::Close(t) => {
-                debug!("close: {:?}", t);
+                debug!("close: {t:?}");
                 self.is_open = false;
                 Err(Error::Closed("Web Socket Closed".to_string()))
             }
@@ -191,42 +388,265 @@ impl WebexEventStream {
         }
     }
 
-    pub(crate) async fn auth(ws_stream: &mut WStream, token: &str) -> Result<(), Error> {
-        /*
-         * Authenticate to the stream
-         */
-        let auth = types::Authorization::new(token);
-        debug!("Authenticating to stream");
-        match ws_stream
-            .send(TMessage::Text(serde_json::to_string(&auth).unwrap()))
-            .await
-        {
-            Ok(()) => {
-                /*
-                 * The next thing back should be a pong
-                 */
-                match ws_stream.next().await {
-                    Some(msg) => match msg {
-                        Ok(msg) => match msg {
-                            TMessage::Ping(_) | TMessage::Pong(_) => {
-                                debug!("Authentication succeeded");
-                                Ok(())
-                            }
-                            _ => Err(format!("Received {msg:?} in reply to auth message").into()),
-                        },
-                        Err(e) => Err(format!("Received error from websocket: {e}").into()),
-                    },
-                    None => Err("Websocket closed".to_string().into()),
+    /// Authenticate to the stream, sending the authentication a second time if Webex doesn't
+    /// acknowledge it within `timeout`
+    pub(crate) async fn auth(
+        ws_stream: &mut WStream,
+        token: &str,
+        timeout: Duration,
+    ) -> Result<(), Error> {
+        let auth = TMessage::Text(serde_json::to_string(&types::Authorization::new(token))?);
+        for attempt in 1..=2 {
+            debug!("Authenticating to stream (attempt {attempt})");
+            ws_stream
+                .send(auth.clone())
+                .await
+                .map_err(|e| Error::Tungstenite(e, "failed to send authentication".to_string()))?;
+            // Webex acknowledges with a Pong (Pings are accepted too)
+            let Ok(reply) = tokio::time::timeout(timeout, ws_stream.next()).await else {
+                warn!("No reply to authentication within {timeout:?}");
+                continue;
+            };
+            return match reply {
+                Some(Ok(TMessage::Ping(_) | TMessage::Pong(_))) => {
+                    debug!("Authentication succeeded");
+                    Ok(())
+                }
+                Some(Ok(msg)) => Err(format!("Received {msg:?} in reply to auth message").into()),
+                Some(Err(e)) => Err(Error::wrap(e, "Received error from websocket")),
+                None => Err("Websocket closed".to_string().into()),
+            };
+        }
+        Err(Error::Closed("Authentication timed out".to_string()))
+    }
+}
+
+/// Event stream that drops events which were already received, see
+/// [`WebexEventStream::with_deduplication()`]
+pub struct DeduplicatingEventStream {
+    inner: WebexEventStream,
+    capacity: usize,
+    seen: HashSet<i64>,
+    order: VecDeque<i64>,
+}
+
+impl DeduplicatingEventStream {
+    /// Get the next event that wasn't seen before
+    ///
+    /// # Errors
+    /// See [`WebexEventStream::next()`].
+    pub async fn next(&mut self) -> Result<Event, Error> {
+        loop {
+            let event = self.inner.next().await?;
+            if self.capacity == 0 {
+                return Ok(event);
+            }
+            if !self.seen.insert(event.sequence_number) {
+                debug!("Skipping duplicate event {}", event.sequence_number);
+                continue;
+            }
+            if self.order.len() == self.capacity {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.seen.remove(&oldest);
+                }
+            }
+            self.order.push_back(event.sequence_number);
+            return Ok(event);
+        }
+    }
+
+    /// Get the wrapped stream, e.g. to check [`WebexEventStream::is_open`]
+    #[must_use]
+    pub const fn inner(&self) -> &WebexEventStream {
+        &self.inner
+    }
+
+    /// Unwrap the underlying stream
+    #[must_use]
+    pub fn into_inner(self) -> WebexEventStream {
+        self.inner
+    }
+
+    /// Swap in a new stream, e.g. after reconnecting with [`Webex::event_stream()`] once the
+    /// current one failed. The recently seen events are kept, so events delivered again by the
+    /// new connection are skipped. Returns the previous stream.
+    pub const fn replace_inner(&mut self, inner: WebexEventStream) -> WebexEventStream {
+        std::mem::replace(&mut self.inner, inner)
+    }
+}
+
+type PageFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<Message>, Error>> + 'a>>;
+
+/// Walks back through the message history of a room, one page at a time.
+///
+/// Created with [`Webex::message_pager()`]. Pages can be fetched explicitly with
+/// [`MessagePager::next_page()`], or the pager can be used as a [`Stream`] of individual
+/// messages, newest first. The two should not be mixed on the same pager.
+#[must_use]
+pub struct MessagePager<'a> {
+    webex: &'a Webex,
+    params: MessageListParams<'a>,
+    cursor: Option<String>,
+    done: bool,
+    buffer: VecDeque<Message>,
+    pending: Option<PageFuture<'a>>,
+}
+
+impl<'a> MessagePager<'a> {
+    const fn new(webex: &'a Webex, params: MessageListParams<'a>) -> Self {
+        Self {
+            webex,
+            params,
+            cursor: None,
+            done: false,
+            buffer: VecDeque::new(),
+            pending: None,
+        }
+    }
+
+    /// Get the next (older) page of messages.
+    ///
+    /// Returns `None` once the start of the room history has been reached.
+    pub async fn next_page(&mut self) -> Result<Option<Vec<Message>>, Error> {
+        if self.done {
+            return Ok(None);
+        }
+        let page = Self::fetch_page(self.webex, self.params.clone(), self.cursor.clone()).await?;
+        self.advance(&page);
+        Ok(if page.is_empty() { None } else { Some(page) })
+    }
+
+    async fn fetch_page(
+        webex: &Webex,
+        params: MessageListParams<'_>,
+        cursor: Option<String>,
+    ) -> Result<Vec<Message>, Error> {
+        let mut params = params;
+        if cursor.is_some() {
+            params.before_message = cursor.as_deref();
+        }
+        webex.list_messages(params).await
+    }
+
+    fn advance(&mut self, page: &[Message]) {
+        // Messages are listed newest first, so the last one is the oldest
+        match page.last().and_then(|msg| msg.id.clone()) {
+            Some(id) => self.cursor = Some(id),
+            None => self.done = true,
+        }
+    }
+}
+
+impl Stream for MessagePager<'_> {
+    type Item = Result<Message, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            if let Some(msg) = this.buffer.pop_front() {
+                return Poll::Ready(Some(Ok(msg)));
+            }
+            if this.done {
+                return Poll::Ready(None);
+            }
+            let pending = this.pending.get_or_insert_with(|| {
+                Box::pin(Self::fetch_page(
+                    this.webex,
+                    this.params.clone(),
+                    this.cursor.clone(),
+                ))
+            });
+            let result = ready!(pending.as_mut().poll(cx));
+            this.pending = None;
+            match result {
+                Ok(page) => {
+                    this.advance(&page);
+                    this.buffer.extend(page);
+                }
+                Err(e) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(e)));
                 }
             }
-            Err(e) => Err(Error::Tungstenite(
-                e,
-                "failed to send authentication".to_string(),
-            )),
         }
     }
 }
 
+/// Authenticated access to REST endpoints that this crate doesn't wrap (yet), e.g. compliance
+/// or calling APIs.
+///
+/// Created with [`Webex::raw_client()`]. Paths are relative to the API root, e.g. `"people/me"`,
+/// and responses can be deserialized into any type, including [`serde_json::Value`].
+#[derive(Clone, Copy)]
+pub struct RawClient<'a> {
+    client: &'a RestClient,
+    token: &'a str,
+}
+
+impl RawClient<'_> {
+    /// GET `path`, with optional query parameters
+    ///
+    /// # Errors
+    /// See [`Webex::get()`].
+    pub async fn get_raw<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        params: Option<impl Serialize>,
+    ) -> Result<T, Error> {
+        self.client
+            .api_get(path, params, AuthorizationType::Bearer(self.token))
+            .await
+    }
+
+    /// POST `body` as JSON to `path`
+    ///
+    /// # Errors
+    /// See [`Webex::get()`].
+    pub async fn post_raw<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: impl Serialize,
+    ) -> Result<T, Error> {
+        self.client
+            .api_post(
+                path,
+                body,
+                None::<()>,
+                AuthorizationType::Bearer(self.token),
+            )
+            .await
+    }
+
+    /// PUT `body` as JSON to `path`
+    ///
+    /// # Errors
+    /// See [`Webex::get()`].
+    pub async fn put_raw<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: impl Serialize,
+    ) -> Result<T, Error> {
+        self.client
+            .api_put(
+                path,
+                body,
+                None::<()>,
+                AuthorizationType::Bearer(self.token),
+            )
+            .await
+    }
+
+    /// DELETE `path`
+    ///
+    /// # Errors
+    /// See [`Webex::get()`].
+    pub async fn delete_raw(&self, path: &str) -> Result<(), Error> {
+        self.client
+            .api_delete(path, None::<()>, AuthorizationType::Bearer(self.token))
+            .await
+    }
+}
+
 enum AuthorizationType<'a> {
     None,
     Bearer(&'a str),
@@ -247,7 +667,10 @@ const BODY_NONE: Option<Body<()>> = None;
 #[derive(Clone)]
 struct RestClient {
     host_prefix: HashMap<String, String>,
+    /// Prefix for resources without an entry in `host_prefix`
+    default_prefix: String,
     web_client: reqwest::Client,
+    tracking_id: Arc<Mutex<Option<String>>>,
 }
 
 impl RestClient {
@@ -255,34 +678,76 @@ impl RestClient {
     pub fn new() -> Self {
         Self {
             host_prefix: HashMap::new(),
+            default_prefix: REST_HOST_PREFIX.to_string(),
             web_client: reqwest::Client::new(),
+            tracking_id: Arc::default(),
         }
     }
 
+    /// Creates a new `RestClient` that sends all requests through a proxy
+    pub fn with_proxy(proxy: reqwest::Proxy) -> Result<Self, Error> {
+        Ok(Self {
+            host_prefix: HashMap::new(),
+            default_prefix: REST_HOST_PREFIX.to_string(),
+            web_client: reqwest::Client::builder().proxy(proxy).build()?,
+            tracking_id: Arc::default(),
+        })
+    }
+
+    /// Tracking ID of the last response received, to quote when contacting Webex support
+    fn last_tracking_id(&self) -> Option<String> {
+        self.tracking_id
+            .lock()
+            .ok()
+            .and_then(|tracking_id| tracking_id.clone())
+    }
+
     /******************************************************************
      * Low-level API.  These calls are chained to build various
      * high-level calls like "get_message"
      ******************************************************************/
 
-    async fn api_get<'a, T: DeserializeOwned>(
+    async fn api_get<T: DeserializeOwned>(
         &self,
-        rest_method: &str,
+        rest_method: impl AsRef<str>,
         params: Option<impl Serialize>,
-        auth: AuthorizationType<'a>,
+        auth: AuthorizationType<'_>,
     ) -> Result<T, Error> {
-        self.rest_api(reqwest::Method::GET, rest_method, auth, params, BODY_NONE)
-            .await
+        self.rest_api(
+            reqwest::Method::GET,
+            rest_method.as_ref(),
+            auth,
+            params,
+            BODY_NONE,
+        )
+        .await
     }
 
-    async fn api_delete<'a>(
+    async fn api_get_with_headers<T: DeserializeOwned>(
         &self,
-        rest_method: &str,
+        rest_method: impl AsRef<str>,
+        params: Option<impl Serialize>,
+        auth: AuthorizationType<'_>,
+    ) -> Result<(T, reqwest::header::HeaderMap), Error> {
+        self.rest_api_with_headers(
+            reqwest::Method::GET,
+            rest_method.as_ref(),
+            auth,
+            params,
+            BODY_NONE,
+        )
+        .await
+    }
+
+    async fn api_delete(
+        &self,
+        rest_method: impl AsRef<str>,
         params: Option<impl Serialize>,
-        auth: AuthorizationType<'a>,
+        auth: AuthorizationType<'_>,
     ) -> Result<(), Error> {
         self.rest_api(
             reqwest::Method::DELETE,
-            rest_method,
+            rest_method.as_ref(),
             auth,
             params,
             BODY_NONE,
@@ -290,17 +755,17 @@ impl RestClient {
         .await
     }
 
-    async fn api_post<'a, T: DeserializeOwned>(
+    async fn api_post<T: DeserializeOwned>(
         &self,
-        rest_method: &str,
+        rest_method: impl AsRef<str>,
         body: impl Serialize,
         params: Option<impl Serialize>,
-        auth: AuthorizationType<'a>,
+        auth: AuthorizationType<'_>,
     ) -> Result<T, Error>
 where {
         self.rest_api(
             reqwest::Method::POST,
-            rest_method,
+            rest_method.as_ref(),
             auth,
             params,
             Some(Body::Json(body)),
@@ -308,16 +773,16 @@ where {
         .await
     }
 
-    async fn api_post_form_urlencoded<'a, T: DeserializeOwned>(
+    async fn api_post_form_urlencoded<T: DeserializeOwned>(
         &self,
-        rest_method: &str,
+        rest_method: impl AsRef<str>,
         body: impl Serialize,
         params: Option<impl Serialize>,
-        auth: AuthorizationType<'a>,
+        auth: AuthorizationType<'_>,
     ) -> Result<T, Error> {
         self.rest_api(
             reqwest::Method::POST,
-            rest_method,
+            rest_method.as_ref(),
             auth,
             params,
             Some(Body::UrlEncoded(body)),
@@ -325,16 +790,16 @@ where {
         .await
     }
 
-    async fn api_put<'a, T: DeserializeOwned>(
+    async fn api_put<T: DeserializeOwned>(
         &self,
-        rest_method: &str,
+        rest_method: impl AsRef<str>,
         body: impl Serialize,
         params: Option<impl Serialize>,
-        auth: AuthorizationType<'a>,
+        auth: AuthorizationType<'_>,
     ) -> Result<T, Error> {
         self.rest_api(
             reqwest::Method::PUT,
-            rest_method,
+            rest_method.as_ref(),
             auth,
             params,
             Some(Body::Json(body)),
@@ -342,6 +807,54 @@ where {
         .await
     }
 
+    /// Turn an absolute URL on the API host, e.g. a file attached to a message, into a path
+    /// (with query) relative to the default prefix.
+    ///
+    /// Other hosts are refused, as requests to them would carry the token.
+    fn api_path(&self, url: &str) -> Result<String, Error> {
+        let parse = |url: &str| {
+            url::Url::parse(url).map_err(|e| Error::wrap(e, format!("Invalid URL {url}")))
+        };
+        let parsed = parse(url)?;
+        let default = parse(&self.default_prefix)?;
+        let base_path = if parsed.origin() == default.origin() {
+            default.path().trim_end_matches('/')
+        } else if parsed.scheme() == "https"
+            && parsed
+                .host_str()
+                .is_some_and(|host| REST_HOSTS.contains(&host))
+        {
+            "/v1"
+        } else {
+            return Err(Error::Other(format!("not a Webex API URL: {url}")));
+        };
+        let path = parsed
+            .path()
+            .strip_prefix(base_path)
+            .and_then(|path| path.strip_prefix('/'))
+            .ok_or_else(|| Error::Other(format!("not a Webex API URL: {url}")))?;
+        Ok(match parsed.query() {
+            Some(query) => format!("{path}?{query}"),
+            None => path.to_string(),
+        })
+    }
+
+    /// GET an absolute URL on the API host, e.g. a file attached to a message, and return the
+    /// response without reading its body.
+    ///
+    /// Other hosts are refused, as the request carries the token.
+    async fn api_get_content(&self, url: &str, token: &str) -> Result<reqwest::Response, Error> {
+        let url = format!("{}/{}", self.default_prefix, self.api_path(url)?);
+        let res = self.web_client.get(url).bearer_auth(token).send().await?;
+        if let Some(error) = rate_limit_error(&res) {
+            return Err(error);
+        }
+        if !res.status().is_success() {
+            return Err(Error::Status(res.status()));
+        }
+        Ok(res)
+    }
+
     async fn rest_api<T: DeserializeOwned>(
         &self,
         http_method: reqwest::Method,
@@ -350,11 +863,27 @@ where {
         params: Option<impl Serialize>,
         body: Option<Body<impl Serialize>>,
     ) -> Result<T, Error> {
+        self.rest_api_with_headers(http_method, url, auth, params, body)
+            .await
+            .map(|(result, _headers)| result)
+    }
+
+    async fn rest_api_with_headers<T: DeserializeOwned>(
+        &self,
+        http_method: reqwest::Method,
+        url: &str,
+        auth: AuthorizationType<'_>,
+        params: Option<impl Serialize>,
+        body: Option<Body<impl Serialize>>,
+    ) -> Result<(T, reqwest::header::HeaderMap), Error> {
         let url_trimmed = url.split('?').next().unwrap_or(url);
+        // Fall back to the resource prefix, so that e.g. "devices/{id}" uses the "devices" host
+        let resource = url_trimmed.split('/').next().unwrap_or(url_trimmed);
         let prefix = self
             .host_prefix
             .get(url_trimmed)
-            .map_or(REST_HOST_PREFIX, String::as_str);
+            .or_else(|| self.host_prefix.get(resource))
+            .unwrap_or(&self.default_prefix);
         let url = format!("{prefix}/{url}");
         let mut request_builder = self.web_client.request(http_method, url);
         if let Some(params) = params {
@@ -379,8 +908,118 @@ where {
             }
         }
         let res = request_builder.send().await?;
-        Ok(res.json().await?)
+        let status = res.status();
+        let tracking_id = res
+            .headers()
+            .get("trackingid")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        if let Ok(mut last) = self.tracking_id.lock() {
+            last.clone_from(&tracking_id);
+        }
+        if let Some(error) = rate_limit_error(&res) {
+            return Err(error);
+        }
+        let headers = res.headers().clone();
+        let body = res.text().await?;
+        if !status.is_success() {
+            return Err(match (body.is_empty(), tracking_id) {
+                (true, _) => Error::Status(status),
+                (false, None) => Error::StatusText(status, body),
+                (false, Some(tracking_id)) => {
+                    Error::StatusText(status, format!("{body} (tracking ID: {tracking_id})"))
+                }
+            });
+        }
+        // Some requests (e.g. DELETE) reply with an empty body
+        let result = serde_json::from_str(if body.is_empty() { "null" } else { &body })?;
+        Ok((result, headers))
+    }
+}
+
+/// Lower the `max` of listing parameters to [`Gettable::MAX_LIST_SIZE`]
+fn clamp_list_max<T: Gettable>(params: &mut T::ListParams<'_>) {
+    let (Some(limit), Some(max)) = (T::MAX_LIST_SIZE, params.max_mut()) else {
+        return;
+    };
+    if max.is_some_and(|max| max > limit) {
+        warn!(
+            "max {max:?} exceeds the limit of {limit} for {}, using {limit}",
+            T::API_ENDPOINT
+        );
+        *max = Some(limit);
+    }
+}
+
+/// Get an [`Error::Limited`] if the response is HTTP 423 or 429, with its `Retry-After`
+fn rate_limit_error(res: &reqwest::Response) -> Option<Error> {
+    let status = res.status();
+    if status != StatusCode::LOCKED && status != StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+    let retry_after = res
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_retry_after);
+    Some(Error::Limited(status, retry_after))
+}
+
+/// Strip the tags from basic HTML as used in messages, turning line breaks and paragraphs into
+/// newlines and decoding the common entities.
+fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = rest[start + 1..start + end].trim().to_ascii_lowercase();
+        let name = tag
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .find(|s| !s.is_empty());
+        if matches!(name, Some("br")) || (tag.starts_with('/') && matches!(name, Some("p"))) {
+            text.push('\n');
+        }
+        rest = &rest[start + end + 1..];
     }
+    text.push_str(rest);
+    text.trim_end()
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Extract the URL of the next page from a `Link` header, e.g.
+/// `<https://webexapis.com/v1/messages?cursor=abc>; rel="next"`.
+fn parse_link_next(value: &str) -> Option<String> {
+    value.split(',').find_map(|link| {
+        let mut parts = link.split(';').map(str::trim);
+        let url = parts.next()?.strip_prefix('<')?.strip_suffix('>')?;
+        parts
+            .any(|param| param == "rel=\"next\"" || param == "rel=next")
+            .then(|| url.to_string())
+    })
+}
+
+/// Parse a `Retry-After` header value into a number of seconds. The header is either a number of
+/// seconds, or an HTTP-date (RFC 7231) to retry after.
+fn parse_retry_after(value: &str) -> Option<i64> {
+    let value = value.trim();
+    value.parse().ok().or_else(|| {
+        chrono::DateTime::parse_from_rfc2822(value)
+            .ok()
+            .map(|date| {
+                (date.with_timezone(&chrono::Utc) - chrono::Utc::now())
+                    .num_seconds()
+                    .max(0)
+            })
+    })
 }
 
 impl Webex {
@@ -391,48 +1030,106 @@ impl Webex {
         Self::new_with_device_name(DEFAULT_DEVICE_NAME, token).await
     }
 
+    /// Constructs a new Webex Teams context from the token in the `BOT_ACCESS_TOKEN` environment
+    /// variable, e.g. `let webex = Webex::from_env()?.await;`
+    ///
+    /// # Errors
+    /// [`Error::Wrapped`] - returned when the variable is not set or not valid Unicode.
+    pub fn from_env() -> Result<impl Future<Output = Self>, Error> {
+        Self::from_env_with_name("BOT_ACCESS_TOKEN")
+    }
+
+    /// Constructs a new Webex Teams context from the token in the environment variable `var`, see
+    /// [`Self::from_env()`].
+    ///
+    /// # Errors
+    /// [`Error::Wrapped`] - returned when the variable is not set or not valid Unicode.
+    pub fn from_env_with_name(var: &str) -> Result<impl Future<Output = Self>, Error> {
+        let token = std::env::var(var).map_err(|e| env_var_error(var, e))?;
+        Ok(async move { Self::new(&token).await })
+    }
+
     /// Constructs a new Webex Teams context from a token and a chosen name
     /// The name is used to identify the device/client with Webex api
     pub async fn new_with_device_name(device_name: &str, token: &str) -> Self {
-        let mut client: RestClient = RestClient {
-            host_prefix: HashMap::new(),
-            web_client: reqwest::Client::new(),
-        };
+        Self::new_with_client(RestClient::new(), device_name, token).await
+    }
 
-        let mut hasher = DefaultHasher::new();
-        hash::Hash::hash_slice(token.as_bytes(), &mut hasher);
-        let id = hasher.finish();
+    /// Constructs a new Webex Teams context from a token, like [`Self::new()`], but fails when
+    /// the setup requests fail instead of falling back to defaults.
+    ///
+    /// This catches e.g. invalid tokens at startup, rather than on the first API call. A device
+    /// is registered if the token has none yet, so registration failures are reported here too.
+    ///
+    /// # Errors
+    /// * [`Error::Status`] | [`Error::StatusText`] - returned when the token is rejected, or
+    ///   the device registration fails.
+    /// * [`Error::Other`] - returned when the token has no organization.
+    /// * See [`Self::get()`] for other errors from the requests.
+    pub async fn try_new(token: &str) -> Result<Self, Error> {
+        Self::try_new_with_device_name(DEFAULT_DEVICE_NAME, token).await
+    }
 
-        // Have to insert this before calling get_mercury_url() since it uses U2C for the catalog
-        // request.
-        client
-            .host_prefix
-            .insert("limited/catalog".to_string(), U2C_HOST_PREFIX.to_string());
+    /// Constructs a new Webex Teams context from a token and a chosen name, see
+    /// [`Self::try_new()`].
+    ///
+    /// # Errors
+    /// See [`Self::try_new()`].
+    pub async fn try_new_with_device_name(device_name: &str, token: &str) -> Result<Self, Error> {
+        Self::try_new_with_client(RestClient::new(), device_name, token).await
+    }
 
-        let mut webex = Self {
-            id,
-            client,
-            token: token.to_string(),
-            device: DeviceData {
-                device_name: Some(DEFAULT_DEVICE_NAME.to_string()),
-                device_type: Some("DESKTOP".to_string()),
-                localized_model: Some("rust".to_string()),
-                model: Some(format!("rust-v{CRATE_VERSION}")),
-                name: Some(device_name.to_owned()),
-                system_name: Some(DEVICE_SYSTEM_NAME.to_string()),
-                system_version: Some(CRATE_VERSION.to_string()),
-                ..DeviceData::default()
-            },
+    /// Constructs a new Webex Teams context for a guest user, from a guest token created with
+    /// [`auth::GuestTokenBuilder`]. The guest token is exchanged for an access token first.
+    ///
+    /// # Errors
+    /// * [`Error::Status`] | [`Error::StatusText`] - returned when the guest token is rejected.
+    pub async fn new_as_guest(jwt: &str) -> Result<Self, Error> {
+        #[derive(serde::Deserialize)]
+        struct JwtLogin {
+            token: String,
+        }
+
+        let client = RestClient::new();
+        let login: JwtLogin = client
+            .rest_api(
+                reqwest::Method::POST,
+                "jwt/login",
+                AuthorizationType::Bearer(jwt),
+                None::<()>,
+                BODY_NONE,
+            )
+            .await?;
+        Ok(Self::new_with_client(client, DEFAULT_DEVICE_NAME, &login.token).await)
+    }
+
+    /// Constructs a new Webex Teams context for another token, with the same device name.
+    /// Reuses the HTTP connection pool of this client, useful when running several bots from the
+    /// same process.
+    pub async fn clone_with_token(&self, new_token: impl Into<String>) -> Self {
+        let client = RestClient {
+            host_prefix: HashMap::new(),
+            default_prefix: self.client.default_prefix.clone(),
+            web_client: self.client.web_client.clone(),
+            tracking_id: Arc::default(),
         };
+        let device_name = self.device.name.as_deref().unwrap_or(DEFAULT_DEVICE_NAME);
+        Self::new_with_client(client, device_name, &new_token.into())
+            .await
+            .with_max_device_attempts(self.max_device_attempts)
+            .with_auth_timeout(self.auth_timeout)
+    }
 
+    async fn new_with_client(client: RestClient, device_name: &str, token: &str) -> Self {
+        let mut webex = Self::without_devices_url(client, device_name, token);
         let devices_url = match webex.get_mercury_url().await {
             Ok(url) => {
-                trace!("Fetched mercury url {}", url);
+                trace!("Fetched mercury url {url}");
                 url
             }
             Err(e) => {
-                debug!("Failed to fetch devices url, falling back to default");
-                debug!("Error: {:?}", e);
+                warn!("Failed to fetch devices url, falling back to default");
+                debug!("Error: {e:?}");
                 DEFAULT_REGISTRATION_HOST_PREFIX.to_string()
             }
         };
@@ -444,7 +1141,134 @@ impl Webex {
         webex
     }
 
+    async fn try_new_with_client(
+        client: RestClient,
+        device_name: &str,
+        token: &str,
+    ) -> Result<Self, Error> {
+        let mut webex = Self::without_devices_url(client, device_name, token);
+        let devices_url = match webex.get_mercury_url().await {
+            Ok(url) => url,
+            Err(Some(e)) => return Err(e),
+            // Only the failure was cached, try again to get the error
+            Err(None) => {
+                let url = webex.get_mercury_url_uncached().await?;
+                webex.cache_mercury_url(Ok(url.clone()));
+                url
+            }
+        };
+        trace!("Fetched mercury url {devices_url}");
+        webex
+            .client
+            .host_prefix
+            .insert("devices".to_string(), devices_url);
+        // Registers a device if there is none yet
+        webex.get_devices().await?;
+        Ok(webex)
+    }
+
+    fn without_devices_url(mut client: RestClient, device_name: &str, token: &str) -> Self {
+        let id = token_id(token);
+
+        // Have to insert this before calling get_mercury_url() since it uses U2C for the catalog
+        // request.
+        client
+            .host_prefix
+            .insert("limited/catalog".to_string(), U2C_HOST_PREFIX.to_string());
+
+        Self {
+            id,
+            client,
+            token: token.to_string(),
+            device: DeviceData {
+                device_name: Some(DEFAULT_DEVICE_NAME.to_string()),
+                device_type: Some("DESKTOP".to_string()),
+                localized_model: Some("rust".to_string()),
+                model: Some(format!("rust-v{CRATE_VERSION}")),
+                name: Some(device_name.to_owned()),
+                system_name: Some(DEVICE_SYSTEM_NAME.to_string()),
+                system_version: Some(CRATE_VERSION.to_string()),
+                ..DeviceData::default()
+            },
+            max_device_attempts: DEFAULT_MAX_DEVICE_ATTEMPTS,
+            auth_timeout: DEFAULT_AUTH_TIMEOUT,
+            me: Arc::default(),
+        }
+    }
+
+    /// Tracking ID of the last response received from the REST API, if any.
+    ///
+    /// Include this in bug reports to Webex support after a failed operation.
+    #[must_use]
+    pub fn last_tracking_id(&self) -> Option<String> {
+        self.client.last_tracking_id()
+    }
+
+    /// Check that the API is reachable and that the token is valid, by fetching the
+    /// authenticated user's details.
+    pub async fn health_check(&self) -> Result<(), Error> {
+        self.get_me().await.map(|_| ())
+    }
+
+    /// Get the details of the authenticated user (i.e. the bot itself).
+    ///
+    /// # Errors
+    /// See [`Webex::get()`].
+    pub async fn get_me(&self) -> Result<Person, Error> {
+        self.client
+            .api_get(
+                "people/me",
+                None::<()>,
+                AuthorizationType::Bearer(&self.token),
+            )
+            .await
+    }
+
+    /// The authenticated user, fetched with [`Webex::get_me()`] on the first call only.
+    /// Fields that can change, like the display name, may be out of date.
+    async fn cached_me(&self) -> Result<&Person, Error> {
+        self.me.get_or_try_init(|| self.get_me()).await
+    }
+
+    /// Check if the event was triggered by the authenticated user (i.e. the bot itself), see
+    /// [`Event::is_from_bot()`]. The user is fetched on the first call only.
+    ///
+    /// # Errors
+    /// See [`Webex::get_me()`].
+    pub async fn event_is_mine(&self, event: &Event) -> Result<bool, Error> {
+        Ok(event.is_from_bot(&self.cached_me().await?.id))
+    }
+
+    /// Sets how many existing devices [`Webex::event_stream()`] tries to connect to before
+    /// registering a new one. Defaults to 3.
+    pub const fn with_max_device_attempts(mut self, n: usize) -> Self {
+        self.max_device_attempts = n;
+        self
+    }
+
+    /// Sets how long [`Webex::event_stream()`] waits for Webex to acknowledge authentication on
+    /// a new connection. The authentication is sent a second time if it isn't acknowledged in
+    /// time. Defaults to 10 seconds.
+    pub const fn with_auth_timeout(mut self, timeout: Duration) -> Self {
+        self.auth_timeout = timeout;
+        self
+    }
+
+    /// Get an event stream handle with a custom inactivity timeout, see
+    /// [`WebexEventStream::set_timeout()`]
+    pub async fn event_stream_with_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<WebexEventStream, Error> {
+        Ok(self.event_stream().await?.set_timeout(timeout))
+    }
+
     /// Get an event stream handle
+    ///
+    /// Connects through the most recently modified of the existing devices, trying up to
+    /// [`Webex::with_max_device_attempts()`] of them, and registers a new device if none works.
+    /// Once connected, the other devices are deleted since they are left over from previous
+    /// runs or not working.
     pub async fn event_stream(&self) -> Result<WebexEventStream, Error> {
         // Helper function to connect to a device
         // refactored out to make it easier to loop through all devices and also lazily create a
@@ -455,22 +1279,24 @@ impl Webex {
                 return Err("Device has no ws_url".into());
             };
             let url = url::Url::parse(ws_url.as_str())
-                .map_err(|_| Error::from("Failed to parse ws_url"))?;
-            debug!("Connecting to {:?}", url);
+                .map_err(|e| Error::wrap(e, "Failed to parse ws_url"))?;
+            debug!("Connecting to {url:?}");
             match connect_async(url.as_str()).await {
                 Ok((mut ws_stream, _response)) => {
-                    debug!("Connected to {}", url);
-                    WebexEventStream::auth(&mut ws_stream, &s.token).await?;
+                    debug!("Connected to {url}");
+                    WebexEventStream::auth(&mut ws_stream, &s.token, s.auth_timeout).await?;
                     debug!("Authenticated");
-                    let timeout = Duration::from_secs(20);
+                    let timeout = DEFAULT_EVENT_STREAM_TIMEOUT;
                     Ok(WebexEventStream {
                         ws_stream,
                         timeout,
+                        stats: EventStreamStats::new(),
+                        last_parse_failure: None,
                         is_open: true,
                     })
                 }
                 Err(e) => {
-                    warn!("Failed to connect to {:?}: {:?}", url, e);
+                    warn!("Failed to connect to {url:?}: {e:?}");
                     Err(Error::Tungstenite(
                         e,
                         "Failed to connect to ws_url".to_string(),
@@ -486,7 +1312,7 @@ impl Webex {
             .await?
             .iter()
             .filter(|d| d.name == self.device.name)
-            .inspect(|d| trace!("Kept device: {}", d))
+            .inspect(|d| trace!("Kept device: {d}"))
             .cloned()
             .collect();
 
@@ -498,24 +1324,34 @@ impl Webex {
                 .cmp(&a.modification_time.unwrap_or_else(chrono::Utc::now))
         });
 
-        for device in devices {
-            if let Ok(event_stream) = connect_device(self, device).await {
+        let delete_devices = |stale: Vec<DeviceData>| async move {
+            for device in stale {
+                if let Err(e) = self.delete_device(&device).await {
+                    warn!("Failed to delete stale device {device}: {e}");
+                }
+            }
+        };
+
+        for i in 0..devices.len().min(self.max_device_attempts) {
+            if let Ok(event_stream) = connect_device(self, devices[i].clone()).await {
                 trace!("Successfully connected to device.");
+                // Newer devices failed, older ones are left over from previous runs
+                devices.remove(i);
+                delete_devices(devices).await;
                 return Ok(event_stream);
             }
         }
 
         // Failed to connect to any existing devices, creating new one
-        connect_device(self, self.setup_devices().await?).await
+        let event_stream = connect_device(self, self.setup_devices().await?).await?;
+        delete_devices(devices).await;
+        Ok(event_stream)
     }
 
     async fn get_mercury_url(&self) -> Result<String, Option<error::Error>> {
         // Bit of a hacky workaround, error::Error does not implement clone
         // TODO: this can be fixed by returning a Result<String, &error::Error>
-        lazy_static::lazy_static! {
-            static ref MERCURY_CACHE: Mutex<HashMap<u64, Result<String, ()>>> = Mutex::new(HashMap::new());
-        }
-        if let Ok(Some(result)) = MERCURY_CACHE
+        if let Ok(Some(result)) = mercury_cache()
             .lock()
             .map(|cache| cache.get(&self.id).cloned())
         {
@@ -524,14 +1360,15 @@ impl Webex {
         }
 
         let mercury_url = self.get_mercury_url_uncached().await;
+        self.cache_mercury_url(mercury_url.as_ref().map_or(Err(()), |url| Ok(url.clone())));
+        mercury_url.map_err(Some)
+    }
 
-        if let Ok(mut cache) = MERCURY_CACHE.lock() {
-            let result = mercury_url.as_ref().map_or(Err(()), |url| Ok(url.clone()));
+    fn cache_mercury_url(&self, result: Result<String, ()>) {
+        if let Ok(mut cache) = mercury_cache().lock() {
             trace!("Saving mercury url to cache: {}=>{:?}", self.id, &result);
             cache.insert(self.id, result);
         }
-
-        mercury_url.map_err(Some)
     }
 
     async fn get_mercury_url_uncached(&self) -> Result<String, error::Error> {
@@ -581,6 +1418,23 @@ impl Webex {
         self.get(id).await
     }
 
+    /// Get the attachment action (i.e. the submitted inputs) of an [`AdaptiveCard`] submit event
+    ///
+    /// # Errors
+    /// * [`Error::Other`] - returned when the event is not an
+    ///   [`ActivityType::AdaptiveCardSubmit`].
+    /// * See [`Webex::get()`] for errors from the request.
+    pub async fn get_attachment_action_for_event(
+        &self,
+        event: &Event,
+    ) -> Result<AttachmentAction, Error> {
+        if !matches!(event.activity_type(), ActivityType::AdaptiveCardSubmit(_)) {
+            return Err(Error::Other("event is not a card submit".to_string()));
+        }
+        // The ID of a card submit activity is the ID of its attachment action
+        self.get(&event.try_global_id()?).await
+    }
+
     /// Get a message by ID
     #[deprecated(
         since = "0.6.3",
@@ -599,6 +1453,33 @@ impl Webex {
         self.delete::<Message>(id).await
     }
 
+    /// Download a file attached to a message, from one of the URLs in [`Message::files`].
+    ///
+    /// The whole file is read into memory; use [`Self::get_file_stream()`] for large files.
+    ///
+    /// # Errors
+    /// * [`Error::Other`] - returned when the URL is not on the Webex API host, to avoid sending
+    ///   the token elsewhere.
+    /// * [`Error::Limited`] - returned on HTTP 423/429 with an optional Retry-After.
+    /// * [`Error::Status`] - returned when the request results in a non-200 code.
+    /// * [`Error::Reqwest`] - returned on network errors.
+    pub async fn get_file_content(&self, url: &str) -> Result<Bytes, Error> {
+        let res = self.client.api_get_content(url, &self.token).await?;
+        Ok(res.bytes().await?)
+    }
+
+    /// Download a file attached to a message in chunks, see [`Self::get_file_content()`].
+    ///
+    /// # Errors
+    /// See [`Self::get_file_content()`]. Errors reading the body are returned by the stream.
+    pub async fn get_file_stream(
+        &self,
+        url: &str,
+    ) -> Result<impl Stream<Item = Result<Bytes, Error>>, Error> {
+        let res = self.client.api_get_content(url, &self.token).await?;
+        Ok(res.bytes_stream().map(|chunk| chunk.map_err(Error::from)))
+    }
+
     /// Get available rooms
     #[deprecated(since = "0.6.3", note = "Please use `webex::list::<Room>()` instead")]
     pub async fn get_rooms(&self) -> Result<Vec<Room>, Error> {
@@ -608,26 +1489,82 @@ impl Webex {
     /// Get all rooms from all organizations that the client belongs to.
     /// Will be slow as does multiple API calls (one to get teamless rooms, one to get teams, then
     /// one per team).
+    #[deprecated(
+        since = "0.11.0",
+        note = "Please use `webex::list_all_rooms()` instead"
+    )]
     pub async fn get_all_rooms(&self) -> Result<Vec<Room>, Error> {
+        self.list_all_rooms().await
+    }
+
+    /// Get all rooms from all organizations that the client belongs to.
+    /// Will be slow as does multiple API calls (one to get teamless rooms, one to get teams, then
+    /// one per team).
+    pub async fn list_all_rooms(&self) -> Result<Vec<Room>, Error> {
         let (mut all_rooms, teams) = try_join!(self.list(), self.list::<Team>())?;
         let futures: Vec<_> = teams
-            .into_iter()
-            .map(|team| {
-                let params = [("teamId", team.id)];
-                self.client.api_get::<ListResult<Room>>(
-                    Room::API_ENDPOINT,
-                    Some(params),
-                    AuthorizationType::Bearer(&self.token),
-                )
-            })
+            .iter()
+            .map(|team| self.list_team_rooms(&team.id))
             .collect();
         let teams_rooms = try_join_all(futures).await?;
-        for room in teams_rooms {
-            all_rooms.extend(room.items);
+        for rooms in teams_rooms {
+            all_rooms.extend(rooms);
         }
         Ok(all_rooms)
     }
 
+    /// Get the rooms of a team
+    ///
+    /// # Arguments
+    /// * `team_id` - ID of the team
+    pub async fn list_team_rooms(&self, team_id: &str) -> Result<Vec<Room>, Error> {
+        self.list_with_params::<Room>(RoomListParams {
+            team_id: Some(team_id),
+            ..Default::default()
+        })
+        .await
+    }
+
+    /// Get the 1:1 rooms the client is in
+    pub async fn list_direct_rooms(&self) -> Result<Vec<Room>, Error> {
+        self.list_with_params::<Room>(RoomListParams {
+            room_type: Some(RoomType::Direct),
+            ..Default::default()
+        })
+        .await
+    }
+
+    /// Get the group rooms the client is in
+    pub async fn list_group_rooms(&self) -> Result<Vec<Room>, Error> {
+        self.list_with_params::<Room>(RoomListParams {
+            room_type: Some(RoomType::Group),
+            ..Default::default()
+        })
+        .await
+    }
+
+    /// Get the Webex meeting details for a room
+    pub async fn get_room_meeting_info(
+        &self,
+        room_id: &GlobalId,
+    ) -> Result<RoomMeetingInfo, Error> {
+        room_id.check_type(GlobalIdType::Room)?;
+        self.client
+            .api_get(
+                format!("{}/{}/meetingInfo", Room::API_ENDPOINT, room_id.id()),
+                None::<()>,
+                AuthorizationType::Bearer(&self.token),
+            )
+            .await
+    }
+
+    /// Get the Webex meeting link for a room
+    pub async fn get_room_meeting_link(&self, room_id: &GlobalId) -> Result<String, Error> {
+        self.get_room_meeting_info(room_id)
+            .await
+            .map(|info| info.meeting_link)
+    }
+
     /// Get available room
     #[deprecated(since = "0.6.3", note = "Please use `webex::get::<Room>(id)` instead")]
     pub async fn get_room(&self, id: &GlobalId) -> Result<Room, Error> {
@@ -657,7 +1594,38 @@ impl Webex {
     ///   value cannot be deserialised. (If this happens, this is a library bug and should be
     ///   reported.)
     /// * [`Error::UTF8`] - returned when the request returns non-UTF8 code.
+    /// * [`Error::Other`] - returned when the message doesn't have exactly one destination, when
+    ///   the text or markdown exceeds [`MAX_MESSAGE_BYTES`] (see
+    ///   [`MessageOut::truncate_to_limit()`]), when the attached cards exceed the 40 KB limit, or
+    ///   when a file URL is not HTTPS.
     pub async fn send_message(&self, message: &MessageOut) -> Result<Message, Error> {
+        message.validate_destination()?;
+        // The API's response to these is an opaque 400
+        if message.text_byte_len() > MAX_MESSAGE_BYTES {
+            return Err(Error::Other(format!(
+                "message text exceeds {MAX_MESSAGE_BYTES} bytes"
+            )));
+        }
+        if message.markdown_byte_len() > MAX_MESSAGE_BYTES {
+            return Err(Error::Other(format!(
+                "message markdown exceeds {MAX_MESSAGE_BYTES} bytes"
+            )));
+        }
+        let attachment_size = message.total_attachment_size();
+        if attachment_size > types::MAX_ATTACHMENTS_SIZE {
+            return Err(Error::Other(format!(
+                "Card exceeds 40KB limit ({attachment_size} bytes), consider splitting it up"
+            )));
+        }
+        // Webex rejects these with an unhelpful 400
+        if let Some(url) = message
+            .files
+            .iter()
+            .flatten()
+            .find(|url| !url.starts_with("https://"))
+        {
+            return Err(Error::Other(format!("file URL must be HTTPS: {url}")));
+        }
         self.client
             .api_post(
                 "messages",
@@ -668,6 +1636,88 @@ impl Webex {
             .await
     }
 
+    /// Send a plain text direct message to the person with the given email address
+    ///
+    /// # Errors
+    /// See [`Webex::send_message()`].
+    pub async fn direct_message(
+        &self,
+        to_email: &str,
+        text: impl Into<String>,
+    ) -> Result<Message, Error> {
+        let message = MessageOut {
+            to_person_email: Some(to_email.to_string()),
+            text: Some(text.into()),
+            ..Default::default()
+        };
+        self.send_message(&message).await
+    }
+
+    /// Send a markdown direct message to the person with the given email address
+    ///
+    /// # Errors
+    /// See [`Webex::send_message()`].
+    pub async fn direct_message_markdown(
+        &self,
+        to_email: &str,
+        md: impl Into<String>,
+    ) -> Result<Message, Error> {
+        let message = MessageOut {
+            to_person_email: Some(to_email.to_string()),
+            markdown: Some(md.into()),
+            ..Default::default()
+        };
+        self.send_message(&message).await
+    }
+
+    /// Send a plain text message to a room
+    ///
+    /// # Errors
+    /// See [`Webex::send_message()`].
+    pub async fn post_to_room(
+        &self,
+        room_id: &str,
+        text: impl Into<String>,
+    ) -> Result<Message, Error> {
+        let message = MessageOut {
+            room_id: Some(room_id.to_string()),
+            text: Some(text.into()),
+            ..Default::default()
+        };
+        self.send_message(&message).await
+    }
+
+    /// Send a message, retrying when rate limited ([`Error::Limited`]).
+    ///
+    /// Waits for as long as the server asks in its `Retry-After` header, or for an exponentially
+    /// increasing delay starting at [`RetryConfig::base_delay`] otherwise.
+    ///
+    /// # Errors
+    /// See [`Webex::send_message()`]. [`Error::Limited`] is returned once
+    /// [`RetryConfig::max_attempts`] is reached.
+    pub async fn send_message_with_retry(
+        &self,
+        message: &MessageOut,
+        config: RetryConfig,
+    ) -> Result<Message, Error> {
+        let mut delay = config.base_delay;
+        let mut attempt = 1;
+        loop {
+            match self.send_message(message).await {
+                Err(Error::Limited(status, retry_after)) if attempt < config.max_attempts => {
+                    let wait = retry_after
+                        .and_then(|secs| u64::try_from(secs).ok())
+                        .map_or(delay, Duration::from_secs);
+                    debug!("{status} sending message, retrying in {wait:?}");
+                    tokio::time::sleep(wait).await;
+                    delay = delay.saturating_mul(2);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
     /// Edit an existing message
     ///
     /// # Arguments
@@ -685,10 +1735,9 @@ impl Webex {
         message_id: &GlobalId,
         params: &MessageEditParams<'_>,
     ) -> Result<Message, Error> {
-        let rest_method = format!("messages/{}", message_id.id());
         self.client
             .api_put(
-                &rest_method,
+                format!("messages/{}", message_id.id()),
                 params,
                 None::<()>,
                 AuthorizationType::Bearer(&self.token),
@@ -696,67 +1745,337 @@ impl Webex {
             .await
     }
 
-    /// Get a resource from an ID
+    /// Update a person's profile, e.g. the display name or avatar of the bot.
+    ///
+    /// Bots can only update their own profile, see [`Webex::update_me()`].
+    ///
     /// # Errors
     /// * [`Error::Limited`] - returned on HTTP 423/429 with an optional Retry-After.
-    /// * [`Error::Status`] | [`Error::StatusText`] - returned when the request results in a non-200 code.
-    /// * [`Error::Json`] - returned when your input object cannot be serialized, or the return
-    ///   value cannot be deserialised. (If this happens, this is a library bug and should be
-    ///   reported.)
-    /// * [`Error::UTF8`] - returned when the request returns non-UTF8 code.
-    pub async fn get<T: Gettable + DeserializeOwned>(&self, id: &GlobalId) -> Result<T, Error> {
-        let rest_method = format!("{}/{}", T::API_ENDPOINT, id.id());
+    /// * [`Error::Status`] | [`Error::StatusText`] - returned when the request results in a non-200 code,
+    ///   e.g. when updating someone else's profile.
+    /// * [`Error::Json`] - returned when the return value cannot be deserialised.
+    pub async fn update_person(
+        &self,
+        person_id: &GlobalId,
+        params: &PersonUpdateParams,
+    ) -> Result<Person, Error> {
+        self.put_person(person_id.id(), params).await
+    }
+
+    /// Update the profile of the authenticated user (i.e. the bot itself).
+    ///
+    /// # Errors
+    /// See [`Webex::update_person()`].
+    pub async fn update_me(&self, params: &PersonUpdateParams) -> Result<Person, Error> {
+        let me = self.cached_me().await?;
+        self.put_person(&me.id, params).await
+    }
+
+    async fn put_person(&self, id: &str, params: &PersonUpdateParams) -> Result<Person, Error> {
         self.client
-            .api_get::<T>(
-                rest_method.as_str(),
+            .api_put(
+                format!("people/{id}"),
+                params,
                 None::<()>,
                 AuthorizationType::Bearer(&self.token),
             )
             .await
     }
 
-    /// Delete a resource from an ID
-    pub async fn delete<T: Gettable + DeserializeOwned>(&self, id: &GlobalId) -> Result<(), Error> {
-        let rest_method = format!("{}/{}", T::API_ENDPOINT, id.id());
+    /// Get the memberships of a room
+    pub async fn list_memberships(&self, room_id: &str) -> Result<Vec<Membership>, Error> {
+        self.list_with_params::<Membership>(MembershipListParams {
+            room_id: Some(room_id),
+            ..Default::default()
+        })
+        .await
+    }
+
+    /// Create a team, with the client as its only member
+    ///
+    /// # Errors
+    /// * [`Error::Limited`] - returned on HTTP 423/429 with an optional Retry-After.
+    /// * [`Error::Status`] | [`Error::StatusText`] - returned when the request results in a non-200 code.
+    /// * [`Error::Json`] - returned when the return value cannot be deserialised.
+    pub async fn create_team(&self, name: &str, description: Option<&str>) -> Result<Team, Error> {
         self.client
-            .api_delete(
-                rest_method.as_str(),
+            .api_post(
+                Team::API_ENDPOINT,
+                TeamParams { name, description },
                 None::<()>,
                 AuthorizationType::Bearer(&self.token),
             )
             .await
     }
 
-    /// List resources of a type
-    pub async fn list<T: Gettable + DeserializeOwned>(&self) -> Result<Vec<T>, Error> {
+    /// Rename a team, and change its description
+    ///
+    /// # Errors
+    /// See [`Webex::create_team()`].
+    pub async fn update_team(
+        &self,
+        id: &GlobalId,
+        name: &str,
+        description: Option<&str>,
+    ) -> Result<Team, Error> {
         self.client
-            .api_get::<ListResult<T>>(
-                T::API_ENDPOINT,
+            .api_put(
+                format!("{}/{}", Team::API_ENDPOINT, id.id()),
+                TeamParams { name, description },
                 None::<()>,
                 AuthorizationType::Bearer(&self.token),
             )
             .await
-            .map(|result| result.items)
     }
 
-    /// List resources of a type, with parameters
-    pub async fn list_with_params<T: Gettable + DeserializeOwned>(
+    /// Delete a team
+    pub async fn delete_team(&self, id: &GlobalId) -> Result<(), Error> {
+        self.delete::<Team>(id).await
+    }
+
+    /// Create a webhook, to have Webex POST events to `target_url`
+    ///
+    /// # Arguments
+    /// * `filter` - limits the events, e.g. `"roomId=<id>"`
+    /// * `secret` - used to sign the payloads, in the `X-Spark-Signature` header
+    ///
+    /// # Errors
+    /// * [`Error::Other`] - returned when `resource` or `event` is `Unknown`.
+    /// * See [`Webex::create_team()`] for errors from the request.
+    pub async fn create_webhook(
         &self,
-        list_params: T::ListParams<'_>,
-    ) -> Result<Vec<T>, Error> {
+        name: &str,
+        target_url: &str,
+        resource: WebhookResource,
+        event: WebhookEvent,
+        filter: Option<&str>,
+        secret: Option<&str>,
+    ) -> Result<Webhook, Error> {
+        if resource == WebhookResource::Unknown || event == WebhookEvent::Unknown {
+            return Err(Error::Other(
+                "webhook resource and event must be known".to_string(),
+            ));
+        }
+        let params = types::WebhookCreateParams {
+            name,
+            target_url,
+            resource,
+            event,
+            filter,
+            secret,
+        };
         self.client
-            .api_get::<ListResult<T>>(
-                T::API_ENDPOINT,
-                Some(list_params),
+            .api_post(
+                Webhook::API_ENDPOINT,
+                params,
+                None::<()>,
                 AuthorizationType::Bearer(&self.token),
             )
             .await
-            .map(|result| result.items)
     }
 
-    async fn get_devices(&self) -> Result<Vec<DeviceData>, Error> {
-        match self
-            .client
+    /// Get the people in a team
+    ///
+    /// Lists the memberships of the team, then fetches the members concurrently.
+    pub async fn list_team_members(&self, team_id: &str) -> Result<Vec<Person>, Error> {
+        let memberships = self
+            .list_with_params::<TeamMembership>(TeamMembershipListParams {
+                team_id,
+                ..Default::default()
+            })
+            .await?;
+        let futures = memberships.into_iter().map(|membership| async move {
+            let id = GlobalId::new(GlobalIdType::Person, membership.person_id)?;
+            self.get::<Person>(&id).await
+        });
+        try_join_all(futures).await
+    }
+
+    /// Promote a room member to moderator, or demote them
+    ///
+    /// # Errors
+    /// * [`Error::Limited`] - returned on HTTP 423/429 with an optional Retry-After.
+    /// * [`Error::Status`] | [`Error::StatusText`] - returned when the request results in a non-200 code.
+    /// * [`Error::Json`] - returned when the return value cannot be deserialised.
+    /// * [`Error::Other`] - returned when `membership_id` is not a membership ID.
+    pub async fn update_membership(
+        &self,
+        membership_id: &GlobalId,
+        is_moderator: bool,
+    ) -> Result<Membership, Error> {
+        membership_id.check_type(GlobalIdType::Membership)?;
+        self.client
+            .api_put(
+                format!("memberships/{}", membership_id.id()),
+                MembershipUpdateParams { is_moderator },
+                None::<()>,
+                AuthorizationType::Bearer(&self.token),
+            )
+            .await
+    }
+
+    /// Get a resource from an ID
+    /// # Errors
+    /// * [`Error::Limited`] - returned on HTTP 423/429 with an optional Retry-After.
+    /// * [`Error::Status`] | [`Error::StatusText`] - returned when the request results in a non-200 code.
+    /// * [`Error::Json`] - returned when your input object cannot be serialized, or the return
+    ///   value cannot be deserialised. (If this happens, this is a library bug and should be
+    ///   reported.)
+    /// * [`Error::UTF8`] - returned when the request returns non-UTF8 code.
+    pub async fn get<T: Gettable + DeserializeOwned>(&self, id: &GlobalId) -> Result<T, Error> {
+        self.client
+            .api_get::<T>(
+                format!("{}/{}", T::API_ENDPOINT, id.id()),
+                None::<()>,
+                AuthorizationType::Bearer(&self.token),
+            )
+            .await
+    }
+
+    /// Delete a resource from an ID
+    pub async fn delete<T: Gettable + DeserializeOwned>(&self, id: &GlobalId) -> Result<(), Error> {
+        self.client
+            .api_delete(
+                format!("{}/{}", T::API_ENDPOINT, id.id()),
+                None::<()>,
+                AuthorizationType::Bearer(&self.token),
+            )
+            .await
+    }
+
+    /// List resources of a type
+    pub async fn list<T: Gettable + DeserializeOwned>(&self) -> Result<Vec<T>, Error> {
+        self.client
+            .api_get::<ListResult<T>>(
+                T::API_ENDPOINT,
+                None::<()>,
+                AuthorizationType::Bearer(&self.token),
+            )
+            .await
+            .map(|result| result.items)
+    }
+
+    /// List resources of a type, with parameters.
+    ///
+    /// A `max` above what the API accepts for the type is lowered to that limit, with a warning.
+    pub async fn list_with_params<T: Gettable + DeserializeOwned>(
+        &self,
+        mut list_params: T::ListParams<'_>,
+    ) -> Result<Vec<T>, Error> {
+        clamp_list_max::<T>(&mut list_params);
+        self.client
+            .api_get::<ListResult<T>>(
+                T::API_ENDPOINT,
+                Some(list_params),
+                AuthorizationType::Bearer(&self.token),
+            )
+            .await
+            .map(|result| result.items)
+    }
+
+    /// List rooms, see [`Webex::list_with_params()`]
+    pub async fn list_rooms(&self, params: RoomListParams<'_>) -> Result<Vec<Room>, Error> {
+        self.list_with_params(params).await
+    }
+
+    /// List room memberships, see [`Webex::list_with_params()`]
+    pub async fn list_memberships_with_params(
+        &self,
+        params: MembershipListParams<'_>,
+    ) -> Result<Vec<Membership>, Error> {
+        self.list_with_params(params).await
+    }
+
+    /// List teams, see [`Webex::list_with_params()`]
+    pub async fn list_teams(&self, params: TeamListParams) -> Result<Vec<Team>, Error> {
+        self.list_with_params(params).await
+    }
+
+    /// List people, see [`Webex::list_with_params()`]
+    pub async fn list_people(&self, params: PeopleListParams<'_>) -> Result<Vec<Person>, Error> {
+        self.list_with_params(params).await
+    }
+
+    /// List one page of resources of a type, with parameters.
+    /// Unlike [`Webex::list_with_params()`], this also returns the link to the next page, if any,
+    /// to pass to [`Webex::list_page_from_cursor()`].
+    pub async fn list_page<T: Gettable + DeserializeOwned>(
+        &self,
+        mut list_params: T::ListParams<'_>,
+    ) -> Result<ListResultMeta<T>, Error> {
+        clamp_list_max::<T>(&mut list_params);
+        self.fetch_page(T::API_ENDPOINT, Some(list_params)).await
+    }
+
+    /// List the page of resources that [`ListResultMeta::next_cursor`] points to.
+    ///
+    /// # Errors
+    /// * [`Error::Other`] - returned when the cursor is not a URL of the Webex API.
+    /// * See [`Webex::list_page()`] for errors from the request.
+    pub async fn list_page_from_cursor<T: Gettable + DeserializeOwned>(
+        &self,
+        cursor: &str,
+    ) -> Result<ListResultMeta<T>, Error> {
+        // The cursor already has the query parameters of the first page
+        let path = self.client.api_path(cursor)?;
+        self.fetch_page(&path, None::<()>).await
+    }
+
+    async fn fetch_page<T: DeserializeOwned>(
+        &self,
+        rest_method: &str,
+        params: Option<impl Serialize>,
+    ) -> Result<ListResultMeta<T>, Error> {
+        let (result, headers) = self
+            .client
+            .api_get_with_headers::<ListResult<T>>(
+                rest_method,
+                params,
+                AuthorizationType::Bearer(&self.token),
+            )
+            .await?;
+        let next_cursor = headers
+            .get_all(reqwest::header::LINK)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .find_map(parse_link_next);
+        Ok(ListResultMeta {
+            items: result.items,
+            next_cursor,
+        })
+    }
+
+    /// List messages in a room
+    ///
+    /// # Arguments
+    /// * `params`: [`MessageListParams`] - the room to list messages from (see
+    ///   [`MessageListParams::new()`]), along with any optional filters.
+    pub async fn list_messages(
+        &self,
+        params: MessageListParams<'_>,
+    ) -> Result<Vec<Message>, Error> {
+        self.list_with_params(params).await
+    }
+
+    /// Page through the message history of a room, starting with the most recent messages.
+    ///
+    /// See [`MessagePager`] for details.
+    pub const fn message_pager<'a>(&'a self, params: MessageListParams<'a>) -> MessagePager<'a> {
+        MessagePager::new(self, params)
+    }
+
+    /// Get a client for REST endpoints that aren't wrapped by this crate, see [`RawClient`]
+    #[must_use]
+    pub fn raw_client(&self) -> RawClient<'_> {
+        RawClient {
+            client: &self.client,
+            token: &self.token,
+        }
+    }
+
+    async fn get_devices(&self) -> Result<Vec<DeviceData>, Error> {
+        match self
+            .client
             .api_get::<DevicesReply>(
                 "devices",
                 None::<()>,
@@ -780,11 +2099,28 @@ impl Webex {
                     }
                 }
                 Error::Limited(_, _) => Err(e),
-                _ => Err(format!("Can't decode devices reply: {e}").into()),
+                _ => Err(Error::wrap(e, "Can't decode devices reply")),
             },
         }
     }
 
+    /// Delete a device registration
+    pub async fn delete_device(&self, device: &DeviceData) -> Result<(), Error> {
+        let device_id = device
+            .url
+            .as_deref()
+            .and_then(|url| url.rsplit('/').next())
+            .ok_or("Device has no url")?;
+        trace!("Deleting device: {device}");
+        self.client
+            .api_delete(
+                format!("devices/{device_id}"),
+                None::<()>,
+                AuthorizationType::Bearer(&self.token),
+            )
+            .await
+    }
+
     async fn setup_devices(&self) -> Result<DeviceData, Error> {
         trace!("Setting up new device: {}", &self.device);
         self.client
@@ -839,6 +2175,59 @@ impl Message {
             ..Default::default()
         }
     }
+
+    /// Get the time the message was created, if set and valid.
+    #[must_use]
+    pub fn created_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.created
+            .as_deref()
+            .and_then(|created| chrono::DateTime::parse_from_rfc3339(created).ok())
+            .map(|created| created.with_timezone(&chrono::Utc))
+    }
+
+    /// Get the readable text of the message: the first non-empty of `text`, `markdown`, or `html`
+    /// with the tags stripped.
+    #[must_use]
+    pub fn plain_text(&self) -> Option<std::borrow::Cow<'_, str>> {
+        fn non_empty(s: Option<&str>) -> Option<&str> {
+            s.filter(|s| !s.trim().is_empty())
+        }
+
+        non_empty(self.text.as_deref())
+            .or_else(|| non_empty(self.markdown.as_deref()))
+            .map(std::borrow::Cow::Borrowed)
+            .or_else(|| {
+                non_empty(self.html.as_deref())
+                    .map(strip_html)
+                    .filter(|text| !text.trim().is_empty())
+                    .map(std::borrow::Cow::Owned)
+            })
+    }
+
+    /// Check if a person is mentioned in the message.
+    /// `person_id` can be either a UUID or a base64 ID.
+    #[must_use]
+    pub fn is_mentioned(&self, person_id: &str) -> bool {
+        self.mentioned_people
+            .iter()
+            .flatten()
+            .any(|mentioned| types::same_person_id(mentioned, person_id))
+    }
+
+    /// Check if everyone in the room was mentioned with `@all`.
+    #[must_use]
+    pub fn mentions_all(&self) -> bool {
+        self.mentioned_groups
+            .iter()
+            .flatten()
+            .any(|group| group == "all")
+    }
+
+    /// Get the first adaptive card attached to the message, if any.
+    #[must_use]
+    pub fn first_card(&self) -> Option<&AdaptiveCard> {
+        self.attachments.iter().flatten().find_map(Attachment::card)
+    }
 }
 
 impl MessageOut {
@@ -855,16 +2244,984 @@ impl MessageOut {
         Self::from(msg)
     }
 
-    /// Add attachment to an existing message
+    /// Check that exactly one of `room_id`, `to_person_id` and `to_person_email` is set
+    ///
+    /// # Errors
+    /// Returns [`Error::Other`] if none or several of the destination fields are set.
+    pub fn validate_destination(&self) -> Result<(), Error> {
+        let destinations = [
+            self.room_id.is_some(),
+            self.to_person_id.is_some(),
+            self.to_person_email.is_some(),
+        ];
+        match destinations.into_iter().filter(|set| *set).count() {
+            1 => Ok(()),
+            0 => Err(Error::Other(
+                "message has no destination: set one of room_id, to_person_id or to_person_email"
+                    .to_string(),
+            )),
+            _ => Err(Error::Other(
+                "message has several destinations: set only one of room_id, to_person_id or \
+                 to_person_email"
+                    .to_string(),
+            )),
+        }
+    }
+
+    /// Length of the plain text of the message, in bytes (0 if unset)
+    #[must_use]
+    pub fn text_byte_len(&self) -> usize {
+        self.text.as_ref().map_or(0, String::len)
+    }
+
+    /// Length of the markdown of the message, in bytes (0 if unset)
+    #[must_use]
+    pub fn markdown_byte_len(&self) -> usize {
+        self.markdown.as_ref().map_or(0, String::len)
+    }
+
+    /// Truncate the text and markdown of the message to [`MAX_MESSAGE_BYTES`], at a character
+    /// boundary, so that [`Webex::send_message()`] doesn't reject it.
+    ///
+    /// Truncated markdown may end in the middle of formatting, e.g. an unclosed code block.
+    ///
+    /// Returns `true` if anything was truncated.
+    pub fn truncate_to_limit(&mut self) -> bool {
+        let mut truncated = false;
+        for field in [&mut self.text, &mut self.markdown].into_iter().flatten() {
+            if field.len() > MAX_MESSAGE_BYTES {
+                let end = (0..=MAX_MESSAGE_BYTES)
+                    .rev()
+                    .find(|&i| field.is_char_boundary(i))
+                    .unwrap_or_default();
+                field.truncate(end);
+                truncated = true;
+            }
+        }
+        truncated
+    }
+
+    /// Total size of the serialized attachments, in bytes. Webex rejects messages where this
+    /// exceeds 40 KB.
+    #[must_use]
+    pub fn total_attachment_size(&self) -> usize {
+        self.attachments
+            .iter()
+            .flatten()
+            .map(|attachment| attachment.content.serialized_size())
+            .sum()
+    }
+
+    /// Add attachment to an existing message, replacing any existing attachments
     ///
     /// # Arguments
     ///
     /// * `card` - Adaptive Card to attach
+    #[deprecated(
+        since = "0.11.0",
+        note = "Please use `push_attachment()` instead, which doesn't replace existing attachments"
+    )]
     pub fn add_attachment(&mut self, card: AdaptiveCard) -> &Self {
-        self.attachments = Some(vec![Attachment {
-            content_type: "application/vnd.microsoft.card.adaptive".to_string(),
-            content: card,
-        }]);
+        self.clear_attachments();
+        self.push_attachment(card)
+    }
+
+    /// Append an attachment to the message. Several cards can be attached to a message, but
+    /// some clients (e.g. mobile) only render the first one.
+    ///
+    /// # Arguments
+    ///
+    /// * `card` - Adaptive Card to attach
+    pub fn push_attachment(&mut self, card: AdaptiveCard) -> &Self {
+        self.attachments
+            .get_or_insert_with(Vec::new)
+            .push(Attachment {
+                content_type: types::ADAPTIVE_CARD_CONTENT_TYPE.to_string(),
+                content: card,
+            });
+        self
+    }
+
+    /// Append an attachment to the message, see [`MessageOut::push_attachment()`]
+    #[must_use]
+    pub fn with_attachment(mut self, card: AdaptiveCard) -> Self {
+        self.push_attachment(card);
+        self
+    }
+
+    /// Set the HTML content of the message, for content that is already formatted. Webex
+    /// rejects messages with both `markdown` and `html`.
+    #[must_use]
+    pub fn with_html(mut self, html: impl Into<String>) -> Self {
+        self.html = Some(html.into());
         self
     }
+
+    /// Remove all attachments from the message
+    pub fn clear_attachments(&mut self) -> &Self {
+        self.attachments = None;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adaptive_card::CardElement;
+    use wiremock::{
+        matchers::{body_json, header, method, path, path_regex, query_param},
+        Mock, ResponseTemplate,
+    };
+
+    pub fn offline_webex() -> Webex {
+        Webex {
+            id: 0,
+            client: RestClient::new(),
+            token: String::new(),
+            device: DeviceData::default(),
+            max_device_attempts: DEFAULT_MAX_DEVICE_ATTEMPTS,
+            auth_timeout: DEFAULT_AUTH_TIMEOUT,
+            me: Arc::default(),
+        }
+    }
+
+    /// Accept one websocket connection on a local port and hand it to `handler`. Returns the
+    /// address to connect to, and the task running `handler`.
+    async fn ws_test_server<F, Fut>(
+        handler: F,
+    ) -> (std::net::SocketAddr, tokio::task::JoinHandle<Fut::Output>)
+    where
+        F: FnOnce(WebSocketStream<TcpStream>) -> Fut + Send + 'static,
+        Fut: Future + Send,
+        Fut::Output: Send + 'static,
+    {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let task = tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            handler(tokio_tungstenite::accept_async(tcp).await.unwrap()).await
+        });
+        (addr, task)
+    }
+
+    #[tokio::test]
+    async fn event_stream_closes_after_close_frame() {
+        let (addr, _) = ws_test_server(|mut ws| async move {
+            ws.close(None).await.unwrap();
+            while ws.next().await.is_some() {}
+        })
+        .await;
+
+        let (ws_stream, _) = connect_async(format!("ws://{addr}")).await.unwrap();
+        let mut stream = WebexEventStream {
+            ws_stream,
+            timeout: Duration::from_secs(5),
+            stats: EventStreamStats::new(),
+            last_parse_failure: None,
+            is_open: true,
+        };
+        assert!(matches!(stream.next().await, Err(Error::Closed(_))));
+        assert!(!stream.is_open);
+
+        // Further reads must not spin or pretend the connection is usable
+        stream.is_open = true;
+        assert!(stream.next().await.is_err());
+        assert!(!stream.is_open);
+    }
+
+    #[tokio::test]
+    async fn auth_times_out_after_retry() {
+        let (addr, server) = ws_test_server(|mut ws| async move {
+            // Never acknowledge, count the authentication attempts
+            let mut received = 0;
+            while let Some(Ok(TMessage::Text(_))) = ws.next().await {
+                received += 1;
+            }
+            received
+        })
+        .await;
+
+        let (mut ws_stream, _) = connect_async(format!("ws://{addr}")).await.unwrap();
+        let result =
+            WebexEventStream::auth(&mut ws_stream, "token", Duration::from_millis(50)).await;
+        assert!(matches!(result, Err(Error::Closed(e)) if e == "Authentication timed out"));
+        drop(ws_stream);
+        assert_eq!(server.await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn deduplicating_stream_skips_repeated_events() {
+        let (addr, _) = ws_test_server(|mut ws| async move {
+            for sequence_number in [1, 2, 1, 3, 1] {
+                let event = Event {
+                    sequence_number,
+                    ..Default::default()
+                };
+                let json = serde_json::to_vec(&event).unwrap();
+                ws.send(TMessage::Binary(json)).await.unwrap();
+            }
+            ws.close(None).await.unwrap();
+            while ws.next().await.is_some() {}
+        })
+        .await;
+
+        let (ws_stream, _) = connect_async(format!("ws://{addr}")).await.unwrap();
+        let mut stream = WebexEventStream {
+            ws_stream,
+            timeout: Duration::from_secs(5),
+            stats: EventStreamStats::new(),
+            last_parse_failure: None,
+            is_open: true,
+        }
+        .with_deduplication(2);
+        let mut received = vec![];
+        while let Ok(event) = stream.next().await {
+            received.push(event.sequence_number);
+        }
+        // 1 has been evicted by the time it is repeated the second time
+        assert_eq!(received, [1, 2, 3, 1]);
+        assert!(!stream.inner().is_open);
+    }
+
+    #[tokio::test]
+    async fn deduplicating_stream_skips_events_repeated_after_reconnection() {
+        async fn connect(sequence_numbers: [i64; 2]) -> WebexEventStream {
+            let (addr, _) = ws_test_server(move |mut ws| async move {
+                for sequence_number in sequence_numbers {
+                    let event = Event {
+                        sequence_number,
+                        ..Default::default()
+                    };
+                    let json = serde_json::to_vec(&event).unwrap();
+                    ws.send(TMessage::Binary(json)).await.unwrap();
+                }
+                ws.close(None).await.unwrap();
+                while ws.next().await.is_some() {}
+            })
+            .await;
+            let (ws_stream, _) = connect_async(format!("ws://{addr}")).await.unwrap();
+            WebexEventStream {
+                ws_stream,
+                timeout: Duration::from_secs(5),
+                stats: EventStreamStats::new(),
+                last_parse_failure: None,
+                is_open: true,
+            }
+        }
+
+        let mut stream = connect([1, 2]).await.with_deduplication(10);
+        let mut received = vec![];
+        while let Ok(event) = stream.next().await {
+            received.push(event.sequence_number);
+        }
+        let old = stream.replace_inner(connect([2, 3]).await);
+        assert!(!old.is_open);
+        while let Ok(event) = stream.next().await {
+            received.push(event.sequence_number);
+        }
+        assert_eq!(received, [1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn event_stream_keeps_unparsable_json() {
+        let (addr, _) = ws_test_server(|mut ws| async move {
+            ws.send(TMessage::Binary(br#"{"id": 42}"#.to_vec()))
+                .await
+                .unwrap();
+            let json = serde_json::to_vec(&Event::default()).unwrap();
+            ws.send(TMessage::Binary(json)).await.unwrap();
+            while ws.next().await.is_some() {}
+        })
+        .await;
+
+        let (ws_stream, _) = connect_async(format!("ws://{addr}")).await.unwrap();
+        let mut stream = WebexEventStream {
+            ws_stream,
+            timeout: Duration::from_secs(5),
+            stats: EventStreamStats::new(),
+            last_parse_failure: None,
+            is_open: true,
+        };
+        assert!(matches!(stream.next().await, Err(Error::Json(_))));
+        assert!(stream.is_open);
+        assert_eq!(stream.last_parse_failure(), Some(r#"{"id": 42}"#));
+        assert!(stream.next().await.is_ok());
+        assert_eq!(stream.stats().errors_encountered, 1);
+    }
+
+    #[tokio::test]
+    async fn downloads_files_from_api_host_only() {
+        use futures::TryStreamExt;
+        let mock = testing::MockWebex::start().await;
+        Mock::given(method("GET"))
+            .and(path("/contents/abc"))
+            .and(header("authorization", "Bearer mock-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"%PDF".to_vec()))
+            .mount(mock.server())
+            .await;
+        let webex = mock.webex();
+        let url = format!("{}/contents/abc", mock.server().uri());
+
+        assert_eq!(webex.get_file_content(&url).await.unwrap(), &b"%PDF"[..]);
+        let chunks: Vec<Bytes> = webex
+            .get_file_stream(&url)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(chunks.concat(), b"%PDF");
+        assert!(matches!(
+            webex
+                .get_file_content("https://example.com/contents/abc")
+                .await,
+            Err(Error::Other(_))
+        ));
+        assert!(matches!(
+            webex.get_file_content(&format!("{url}/missing")).await,
+            Err(Error::Status(StatusCode::NOT_FOUND))
+        ));
+        assert!(matches!(
+            webex
+                .get_file_content("http://webexapis.com/v1/contents/abc")
+                .await,
+            Err(Error::Other(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn downloads_files_from_public_api_url() {
+        let mock = testing::MockWebex::start().await;
+        Mock::given(method("GET"))
+            .and(path("/contents/abc"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"%PDF".to_vec()))
+            .mount(mock.server())
+            .await;
+
+        let content = mock
+            .webex()
+            .get_file_content("https://webexapis.com/v1/contents/abc")
+            .await
+            .unwrap();
+        assert_eq!(content, &b"%PDF"[..]);
+    }
+
+    #[tokio::test]
+    async fn event_stream_limits_device_attempts() {
+        // Accepts connections and drops them, counting attempts
+        let dead = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let dead_addr = dead.local_addr().unwrap();
+        let attempts = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = Arc::clone(&attempts);
+        tokio::spawn(async move {
+            while dead.accept().await.is_ok() {
+                counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            }
+        });
+        let (alive_addr, _) = ws_test_server(|mut ws| async move {
+            ws.next().await;
+            ws.send(TMessage::Pong(vec![])).await.unwrap();
+            while ws.next().await.is_some() {}
+        })
+        .await;
+
+        let mock = testing::MockWebex::start().await;
+        let device = |i: usize, addr| {
+            serde_json::json!({
+                "url": format!("https://devices.example.com/devices/d{i}"),
+                "webSocketUrl": format!("ws://{addr}"),
+            })
+        };
+        let existing: Vec<_> = (0..5).map(|i| device(i, dead_addr)).collect();
+        mock.stub_json(
+            "GET",
+            "/devices",
+            &serde_json::json!({ "devices": existing }),
+        )
+        .await;
+        mock.stub_json("POST", "/devices", &device(5, alive_addr))
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path_regex("^/devices/d[0-4]$"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(5)
+            .mount(mock.server())
+            .await;
+
+        let webex = mock.webex().with_max_device_attempts(2);
+        let stream = webex.event_stream().await.unwrap();
+        assert!(stream.is_open);
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 2);
+        mock.server().verify().await;
+    }
+
+    #[tokio::test]
+    async fn list_team_members() {
+        let person_id =
+            "Y2lzY29zcGFyazovL3VzL1BFT1BMRS82YmIwODVmYS1mNmIyLTQyMTAtYjI2Ny1iZTBmZGViYjA3YzQ";
+        let mock = testing::MockWebex::start().await;
+        Mock::given(method("GET"))
+            .and(path("/team/memberships"))
+            .and(query_param("teamId", "team"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [{
+                    "id": "membership",
+                    "teamId": "team",
+                    "personId": person_id,
+                    "personEmail": "alice@example.com",
+                    "created": "2024-01-01T00:00:00.000Z"
+                }]
+            })))
+            .mount(mock.server())
+            .await;
+        mock.stub_json(
+            "GET",
+            &format!("/people/{person_id}"),
+            &serde_json::json!({ "id": person_id, "displayName": "Alice" }),
+        )
+        .await;
+
+        let members = mock.webex().list_team_members("team").await.unwrap();
+        assert_eq!(members.len(), 1);
+        assert_eq!(members[0].display_name, "Alice");
+    }
+
+    #[tokio::test]
+    async fn list_people_by_email() {
+        let mock = testing::MockWebex::start().await;
+        Mock::given(method("GET"))
+            .and(path("/people"))
+            .and(query_param("email", "alice@example.com"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [{"displayName": "Alice"}]
+            })))
+            .mount(mock.server())
+            .await;
+
+        let people = mock
+            .webex()
+            .list_people(PeopleListParams {
+                email: Some("alice@example.com"),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(people[0].display_name, "Alice");
+    }
+
+    #[tokio::test]
+    async fn create_team() {
+        let mock = testing::MockWebex::start().await;
+        Mock::given(method("POST"))
+            .and(path("/teams"))
+            .and(body_json(serde_json::json!({"name": "Project X"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "team",
+                "name": "Project X",
+                "created": "2024-01-01T00:00:00.000Z"
+            })))
+            .mount(mock.server())
+            .await;
+
+        let team = mock.webex().create_team("Project X", None).await.unwrap();
+        assert_eq!(team.id, "team");
+    }
+
+    #[tokio::test]
+    async fn create_webhook() {
+        let mock = testing::MockWebex::start().await;
+        Mock::given(method("POST"))
+            .and(path("/webhooks"))
+            .and(body_json(serde_json::json!({
+                "name": "cards",
+                "targetUrl": "https://example.com/hook",
+                "resource": "attachmentActions",
+                "event": "created"
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "webhook",
+                "name": "cards",
+                "targetUrl": "https://example.com/hook",
+                "resource": "attachmentActions",
+                "event": "created"
+            })))
+            .mount(mock.server())
+            .await;
+
+        let webhook = mock
+            .webex()
+            .create_webhook(
+                "cards",
+                "https://example.com/hook",
+                WebhookResource::AttachmentActions,
+                WebhookEvent::Created,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(webhook.resource, WebhookResource::AttachmentActions);
+    }
+
+    #[tokio::test]
+    async fn create_webhook_rejects_unknown_resource() {
+        let mock = testing::MockWebex::start().await;
+        let result = mock
+            .webex()
+            .create_webhook(
+                "cards",
+                "https://example.com/hook",
+                WebhookResource::Unknown,
+                WebhookEvent::Created,
+                None,
+                None,
+            )
+            .await;
+        assert!(matches!(result, Err(Error::Other(_))));
+    }
+
+    #[test]
+    fn wrapped_error_has_source() {
+        let parse_error = url::Url::parse("not a url").unwrap_err();
+        let error = Error::wrap(parse_error, "Failed to parse ws_url");
+        assert_eq!(
+            error.to_string(),
+            format!("Failed to parse ws_url: {parse_error}")
+        );
+        let source = std::error::Error::source(&error).unwrap();
+        assert_eq!(source.to_string(), parse_error.to_string());
+    }
+
+    #[tokio::test]
+    async fn try_new_reports_rejected_token() {
+        let mock = testing::MockWebex::start().await;
+        mock.stub_status("GET", "/organizations", 401).await;
+        let mut client = RestClient::new();
+        client.default_prefix = mock.server().uri();
+
+        let result = Webex::try_new_with_client(client.clone(), "test", "rejected-token").await;
+        assert!(matches!(
+            result,
+            Err(Error::Status(StatusCode::UNAUTHORIZED))
+        ));
+        // The failure is cached for new(), but must still be reported
+        let webex = Webex::new_with_client(client.clone(), "test", "rejected-token").await;
+        assert_eq!(
+            webex.client.host_prefix["devices"],
+            DEFAULT_REGISTRATION_HOST_PREFIX
+        );
+        let result = Webex::try_new_with_client(client, "test", "rejected-token").await;
+        assert!(matches!(
+            result,
+            Err(Error::Status(StatusCode::UNAUTHORIZED))
+        ));
+    }
+
+    #[tokio::test]
+    async fn try_new_reports_device_registration_failure() {
+        let mock = testing::MockWebex::start().await;
+        mock.stub_status("GET", "/devices", 404).await;
+        mock.stub_status("POST", "/devices", 403).await;
+        mercury_cache()
+            .lock()
+            .unwrap()
+            .insert(token_id("unregistered-token"), Ok(mock.server().uri()));
+
+        let result =
+            Webex::try_new_with_client(RestClient::new(), "test", "unregistered-token").await;
+        assert!(matches!(result, Err(Error::Status(StatusCode::FORBIDDEN))));
+    }
+
+    #[tokio::test]
+    async fn clone_with_token_keeps_settings() {
+        mercury_cache()
+            .lock()
+            .unwrap()
+            .insert(token_id("cloned-token"), Ok("https://mercury".to_string()));
+        let webex = offline_webex()
+            .with_max_device_attempts(7)
+            .with_auth_timeout(Duration::from_secs(1));
+
+        let clone = webex.clone_with_token("cloned-token").await;
+        assert_eq!(clone.token, "cloned-token");
+        assert_eq!(clone.max_device_attempts, 7);
+        assert_eq!(clone.auth_timeout, Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn raw_client_requests() {
+        let mock = testing::MockWebex::start().await;
+        Mock::given(method("GET"))
+            .and(path("/people/me"))
+            .and(query_param("callingData", "true"))
+            .and(header("authorization", "Bearer mock-token"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "id": "me" })),
+            )
+            .mount(mock.server())
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/things"))
+            .and(body_json(serde_json::json!({ "name": "x" })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "id": "1" })),
+            )
+            .mount(mock.server())
+            .await;
+        mock.stub_status("DELETE", "/things/1", 204).await;
+        let webex = mock.webex();
+        let raw = webex.raw_client();
+
+        let me: serde_json::Value = raw
+            .get_raw("people/me", Some([("callingData", "true")]))
+            .await
+            .unwrap();
+        assert_eq!(me["id"], "me");
+        let created: serde_json::Value = raw
+            .post_raw("things", serde_json::json!({ "name": "x" }))
+            .await
+            .unwrap();
+        assert_eq!(created["id"], "1");
+        raw.delete_raw("things/1").await.unwrap();
+    }
+
+    #[test]
+    fn list_max_is_clamped() {
+        let mut params = MessageListParams {
+            max: Some(99_999),
+            ..MessageListParams::new("room")
+        };
+        clamp_list_max::<Message>(&mut params);
+        assert_eq!(params.max, Some(1000));
+        params.max = Some(10);
+        clamp_list_max::<Message>(&mut params);
+        assert_eq!(params.max, Some(10));
+        let mut params = RoomListParams {
+            max: Some(99_999),
+            ..Default::default()
+        };
+        clamp_list_max::<Room>(&mut params);
+        assert_eq!(params.max, Some(99_999));
+    }
+
+    #[tokio::test]
+    async fn list_pages_from_cursor() {
+        let mock = testing::MockWebex::start().await;
+        let org = |id: &str| Organization {
+            id: id.to_string(),
+            display_name: None,
+            created: String::new(),
+        };
+        let next = format!("{}/organizations?max=1&cursor=abc", mock.server().uri());
+        Mock::given(method("GET"))
+            .and(path("/organizations"))
+            .and(query_param("cursor", "abc"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "items": [org("2")] })),
+            )
+            .mount(mock.server())
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/organizations"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("link", format!("<{next}>; rel=\"next\"").as_str())
+                    .set_body_json(serde_json::json!({ "items": [org("1")] })),
+            )
+            .mount(mock.server())
+            .await;
+        let webex = mock.webex();
+
+        let first = webex
+            .list_page::<Organization>(OrganizationListParams {
+                max: Some(1),
+                calling_data: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(first.items, [org("1")]);
+        let cursor = first.next_cursor.unwrap();
+        let second = webex
+            .list_page_from_cursor::<Organization>(&cursor)
+            .await
+            .unwrap();
+        assert_eq!(second.items, [org("2")]);
+        assert_eq!(second.next_cursor, None);
+        // Cursors from the live API point at its public host
+        let public = webex
+            .list_page_from_cursor::<Organization>(
+                "https://webexapis.com/v1/organizations?max=1&cursor=abc",
+            )
+            .await
+            .unwrap();
+        assert_eq!(public.items, [org("2")]);
+        assert!(webex
+            .list_page_from_cursor::<Organization>("https://example.com/organizations?cursor=abc")
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn from_env_reports_missing_variable() {
+        let result = Webex::from_env_with_name("WEBEX_RUST_TEST_UNSET_TOKEN");
+        assert!(
+            matches!(result, Err(Error::Wrapped { message, .. }) if message == "WEBEX_RUST_TEST_UNSET_TOKEN not set")
+        );
+    }
+
+    #[test]
+    fn env_var_error_reports_invalid_unicode() {
+        let e = std::env::VarError::NotUnicode(std::ffi::OsString::new());
+        assert!(
+            matches!(env_var_error("TOKEN", e), Error::Wrapped { message, .. } if message == "TOKEN is not valid Unicode")
+        );
+    }
+
+    #[test]
+    fn token_id_is_stable() {
+        assert_eq!(token_id(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(token_id("a"), 0xaf63_dc4c_8601_ec8c);
+        assert_ne!(token_id("token-1"), token_id("token-2"));
+    }
+
+    #[tokio::test]
+    async fn oversized_card_rejected() {
+        let mut card = AdaptiveCard::new();
+        let overhead = card
+            .clone()
+            .add_body(CardElement::text_block(""))
+            .serialized_size();
+        card.add_body(CardElement::text_block(
+            "a".repeat(types::MAX_ATTACHMENTS_SIZE + 1 - overhead),
+        ));
+        assert_eq!(card.serialized_size(), types::MAX_ATTACHMENTS_SIZE + 1);
+
+        let mut message = MessageOut {
+            room_id: Some("room".to_string()),
+            ..Default::default()
+        };
+        message.push_attachment(card);
+        let result = offline_webex().send_message(&message).await;
+        assert!(matches!(result, Err(Error::Other(e)) if e.contains("40KB")));
+    }
+
+    #[tokio::test]
+    async fn oversized_text_rejected_or_truncated() {
+        let mut message = MessageOut {
+            room_id: Some("room".to_string()),
+            text: Some("é".repeat(MAX_MESSAGE_BYTES / 2 + 1)),
+            markdown: Some("a".repeat(MAX_MESSAGE_BYTES)),
+            ..Default::default()
+        };
+        assert_eq!(message.text_byte_len(), MAX_MESSAGE_BYTES + 1);
+        let result = offline_webex().send_message(&message).await;
+        assert!(matches!(result, Err(Error::Other(e)) if e.contains("text exceeds")));
+
+        assert!(message.truncate_to_limit());
+        // Cut before the 2-byte character that straddles the limit
+        assert_eq!(message.text_byte_len(), MAX_MESSAGE_BYTES - 1);
+        assert_eq!(message.markdown_byte_len(), MAX_MESSAGE_BYTES);
+        assert!(!message.truncate_to_limit());
+    }
+
+    #[test]
+    fn validate_destination() {
+        for mask in 0..8 {
+            let set = |bit: u8| (mask & bit != 0).then(|| "x".to_string());
+            let message = MessageOut {
+                room_id: set(1),
+                to_person_id: set(2),
+                to_person_email: set(4),
+                ..Default::default()
+            };
+            let valid = [1, 2, 4].contains(&mask);
+            assert_eq!(message.validate_destination().is_ok(), valid, "{message:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn send_message_requires_destination() {
+        let result = offline_webex().send_message(&MessageOut::default()).await;
+        assert!(matches!(result, Err(Error::Other(e)) if e.contains("no destination")));
+    }
+
+    #[tokio::test]
+    async fn update_me_fetches_me_once() {
+        let mock = testing::MockWebex::start().await;
+        Mock::given(method("GET"))
+            .and(path("/people/me"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "id": "bot" })),
+            )
+            .expect(1)
+            .mount(mock.server())
+            .await;
+        mock.stub_json(
+            "PUT",
+            "/people/bot",
+            &serde_json::json!({ "id": "bot", "displayName": "Bot" }),
+        )
+        .await;
+        let webex = mock.webex();
+        let params = PersonUpdateParams::default();
+
+        webex.update_me(&params).await.unwrap();
+        let updated = webex.update_me(&params).await.unwrap();
+        assert_eq!(updated.display_name, "Bot");
+        mock.server().verify().await;
+    }
+
+    #[tokio::test]
+    async fn event_is_mine() {
+        let mock = testing::MockWebex::start().await;
+        mock.stub_json(
+            "GET",
+            "/people/me",
+            &serde_json::json!({
+                "id": "Y2lzY29zcGFyazovL3VzL1BFT1BMRS82YmIwODVmYS1mNmIyLTQyMTAtYjI2Ny1iZTBmZGViYjA3YzQ"
+            }),
+        )
+        .await;
+        let webex = mock.webex();
+        let event_from = |actor: &str| {
+            let mut activity = Activity::default();
+            activity.actor.id = actor.to_string();
+            Event {
+                data: EventData {
+                    activity: Some(activity),
+                    ..EventData::default()
+                },
+                ..Event::default()
+            }
+        };
+
+        let mine = event_from("6bb085fa-f6b2-4210-b267-be0fdebb07c4");
+        assert!(webex.event_is_mine(&mine).await.unwrap());
+        let other = event_from("1eb65fdf-9643-417f-9974-ad72cae0e10f");
+        assert!(!webex.event_is_mine(&other).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn update_membership_rejects_other_ids() {
+        let room_id = GlobalId::new(
+            GlobalIdType::Room,
+            "6bb085fa-f6b2-4210-b267-be0fdebb07c4".to_string(),
+        )
+        .unwrap();
+        let result = offline_webex().update_membership(&room_id, true).await;
+        assert!(matches!(result, Err(Error::Other(e)) if e.contains("does not match")));
+    }
+
+    #[tokio::test]
+    async fn attachment_action_requires_card_submit() {
+        let result = offline_webex()
+            .get_attachment_action_for_event(&Event::default())
+            .await;
+        assert!(matches!(result, Err(Error::Other(_))));
+    }
+
+    #[test]
+    fn message_out_html() {
+        let message = MessageOut::default().with_html("<b>hi</b>");
+        assert_eq!(
+            serde_json::to_value(&message).unwrap(),
+            serde_json::json!({"html": "<b>hi</b>"})
+        );
+    }
+
+    #[tokio::test]
+    async fn non_https_file_rejected() {
+        let message = MessageOut {
+            room_id: Some("room".to_string()),
+            files: Some(vec!["http://example.com/report.pdf".to_string()]),
+            ..Default::default()
+        };
+        // Rejected before any request is made, otherwise this would be a network or HTTP error
+        let result = offline_webex().send_message(&message).await;
+        assert!(matches!(result, Err(Error::Other(e)) if e.contains("HTTPS")));
+    }
+
+    #[test]
+    fn multiple_attachments() {
+        let message = MessageOut::default()
+            .with_attachment(AdaptiveCard::new())
+            .with_attachment(AdaptiveCard::new());
+        assert_eq!(message.attachments.as_ref().map(Vec::len), Some(2));
+    }
+
+    #[tokio::test]
+    async fn send_message_retries_when_limited() {
+        let mock = testing::MockWebex::start().await;
+        Mock::given(method("POST"))
+            .and(path("/messages"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .up_to_n_times(2)
+            .mount(mock.server())
+            .await;
+        mock.stub_json("POST", "/messages", &serde_json::json!({ "id": "ok" }))
+            .await;
+        let webex = mock.webex();
+        let message = MessageOut {
+            room_id: Some("room".to_string()),
+            text: Some("hello".to_string()),
+            ..Default::default()
+        };
+
+        let config = RetryConfig {
+            max_attempts: 1,
+            ..Default::default()
+        };
+        let limited = webex.send_message_with_retry(&message, config).await;
+        assert!(matches!(limited, Err(Error::Limited(_, Some(0)))));
+
+        let sent = webex
+            .send_message_with_retry(&message, RetryConfig::default())
+            .await
+            .unwrap();
+        assert_eq!(sent.id.as_deref(), Some("ok"));
+    }
+
+    #[test]
+    fn message_plain_text() {
+        let mut message = Message {
+            html: Some(
+                "<p>Hi <strong>all</strong>,<br/>see <a href=\"https://example.com\">this</a> &amp; <em>that</em></p>"
+                    .to_string(),
+            ),
+            ..Default::default()
+        };
+        assert_eq!(
+            message.plain_text().as_deref(),
+            Some("Hi all,\nsee this & that")
+        );
+        message.markdown = Some("Hi **all**".to_string());
+        assert_eq!(message.plain_text().as_deref(), Some("Hi **all**"));
+        message.text = Some("Hi all".to_string());
+        assert_eq!(message.plain_text().as_deref(), Some("Hi all"));
+        assert_eq!(Message::default().plain_text(), None);
+    }
+
+    #[test]
+    fn link_header_parsing() {
+        assert_eq!(
+            parse_link_next(r#"<https://webexapis.com/v1/messages?cursor=abc>; rel="next""#),
+            Some("https://webexapis.com/v1/messages?cursor=abc".to_string())
+        );
+        assert_eq!(
+            parse_link_next(r#"<https://a/prev>; rel="prev", <https://a/next>; rel="next""#),
+            Some("https://a/next".to_string())
+        );
+        assert_eq!(parse_link_next(r#"<https://a/prev>; rel="prev""#), None);
+    }
+
+    #[test]
+    fn retry_after_parsing() {
+        assert_eq!(parse_retry_after("120"), Some(120));
+        assert_eq!(parse_retry_after(" 5 "), Some(5));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), Some(0));
+        let later = (chrono::Utc::now() + chrono::Duration::seconds(60)).to_rfc2822();
+        assert!(parse_retry_after(&later).is_some_and(|s| (55..=60).contains(&s)));
+        assert_eq!(parse_retry_after("soon"), None);
+    }
 }
@@ -0,0 +1,154 @@
+#![deny(missing_docs)]
+//! Mock Webex REST API, for testing bots without credentials or network access.
+//!
+//! Requires the `testing` feature.
+//!
+//! ```
+//! # #[tokio::main]
+//! # async fn main() {
+//! use webex::{testing::MockWebex, Message};
+//!
+//! let mock = MockWebex::start().await;
+//! let reply = Message {
+//!     text: Some("pong".to_string()),
+//!     ..Default::default()
+//! };
+//! mock.stub_send_message("room-id", &reply).await;
+//!
+//! let webex = mock.webex();
+//! let sent = webex.post_to_room("room-id", "pong").await.unwrap();
+//! assert_eq!(sent, reply);
+//! # }
+//! ```
+//!
+//! Requests that match no stub get a 404 response. For anything the stubs don't cover, mount
+//! your own [`wiremock::Mock`]s on [`MockWebex::server()`].
+
+use crate::{DeviceData, GlobalId, Message, RestClient, Room, Webex};
+use serde::Serialize;
+use serde_json::json;
+use wiremock::{
+    matchers::{body_partial_json, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+/// A local HTTP server standing in for the Webex REST API
+pub struct MockWebex {
+    server: MockServer,
+}
+
+impl MockWebex {
+    /// Start a mock server without any stubs
+    pub async fn start() -> Self {
+        Self {
+            server: MockServer::start().await,
+        }
+    }
+
+    /// Get a client that sends its REST requests to this mock server.
+    ///
+    /// The client has no device registered, so it can't open an event stream.
+    pub fn webex(&self) -> Webex {
+        let mut client = RestClient::new();
+        client.default_prefix = self.server.uri();
+        Webex {
+            id: 0,
+            client,
+            token: "mock-token".to_string(),
+            device: DeviceData::default(),
+            max_device_attempts: crate::DEFAULT_MAX_DEVICE_ATTEMPTS,
+            auth_timeout: crate::DEFAULT_AUTH_TIMEOUT,
+            me: std::sync::Arc::default(),
+        }
+    }
+
+    /// Get the underlying server, to mount custom mocks
+    #[must_use]
+    pub const fn server(&self) -> &MockServer {
+        &self.server
+    }
+
+    /// Respond with `response` to messages sent to the room with ID `expected_room_id`
+    pub async fn stub_send_message(&self, expected_room_id: &str, response: &Message) {
+        Mock::given(method("POST"))
+            .and(path("/messages"))
+            .and(body_partial_json(json!({ "roomId": expected_room_id })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(response))
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Respond with `response` to requests for the message with the given ID
+    pub async fn stub_get_message(&self, id: &GlobalId, response: &Message) {
+        Mock::given(method("GET"))
+            .and(path(format!("/messages/{}", id.id())))
+            .respond_with(ResponseTemplate::new(200).set_body_json(response))
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Respond with `response` as JSON to `method` requests for `path`, e.g. `"/people/me"`
+    pub async fn stub_json(&self, method_: &str, path_: &str, response: &impl Serialize) {
+        Mock::given(method(method_))
+            .and(path(path_))
+            .respond_with(ResponseTemplate::new(200).set_body_json(response))
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Respond with an empty body and the given status code to `method` requests for `path`
+    pub async fn stub_status(&self, method_: &str, path_: &str, status: u16) {
+        Mock::given(method(method_))
+            .and(path(path_))
+            .respond_with(ResponseTemplate::new(status))
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Respond with `rooms` to requests listing rooms
+    pub async fn stub_list_rooms(&self, rooms: &[Room]) {
+        Mock::given(method("GET"))
+            .and(path("/rooms"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "items": rooms })))
+            .mount(&self.server)
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::Error, GlobalIdType};
+
+    #[tokio::test]
+    async fn stubs() {
+        let mock = MockWebex::start().await;
+        let id = GlobalId::new(
+            GlobalIdType::Message,
+            "1ab849e0-9ab4-11ee-a70f-d9b57e49f8bf".to_string(),
+        )
+        .unwrap();
+        let message = Message {
+            id: Some(id.id().to_string()),
+            text: Some("hi".to_string()),
+            ..Default::default()
+        };
+        mock.stub_get_message(&id, &message).await;
+        mock.stub_send_message("room", &message).await;
+        mock.stub_list_rooms(&[]).await;
+        mock.stub_json("GET", "/people/me", &json!({ "displayName": "bot" }))
+            .await;
+        mock.stub_status("DELETE", "/messages/gone", 204).await;
+        let webex = mock.webex();
+
+        assert_eq!(webex.get::<Message>(&id).await.unwrap(), message);
+        assert_eq!(webex.post_to_room("room", "hi").await.unwrap(), message);
+        assert!(matches!(
+            webex.post_to_room("other room", "hi").await,
+            Err(Error::Status(_) | Error::StatusText(..))
+        ));
+        assert!(webex.list::<Room>().await.unwrap().is_empty());
+        assert_eq!(webex.get_me().await.unwrap().display_name, "bot");
+        assert!(webex.raw_client().delete_raw("messages/gone").await.is_ok());
+    }
+}
@@ -9,13 +9,14 @@ use std::convert::TryFrom;
 use std::{collections::HashMap, fmt};
 use uuid::Uuid;
 
-pub(crate) use api::{Gettable, ListResult};
+pub(crate) use api::{Gettable, ListParams, ListResult};
 
 mod api {
     //! Private crate to hold all types that the user shouldn't have to interact with.
     use super::{
-        AttachmentAction, Message, MessageListParams, Organization, Person, Room, RoomListParams,
-        Team,
+        AttachmentAction, License, LicenseListParams, Membership, MembershipListParams, Message,
+        MessageListParams, Organization, OrganizationListParams, PeopleListParams, Person, Room,
+        RoomListParams, Team, TeamListParams, TeamMembership, TeamMembershipListParams, Webhook,
     };
 
     /// Trait for API types. Has to be public due to trait bounds limitations on webex API, but hidden
@@ -24,20 +25,54 @@ mod api {
         /// Endpoint to query to perform an HTTP GET request with an id (to get an instance), or
         /// without an id (to list them).
         const API_ENDPOINT: &'static str;
-        type ListParams<'a>: serde::Serialize;
+        type ListParams<'a>: ListParams;
+        /// Largest `max` accepted by the API when listing, if any. Larger values are clamped.
+        const MAX_LIST_SIZE: Option<u32> = None;
     }
 
+    /// Parameters for listing a [`Gettable`] type
+    pub trait ListParams: serde::Serialize {
+        /// The `max` parameter, if the listing has one
+        fn max_mut(&mut self) -> Option<&mut Option<u32>> {
+            None
+        }
+    }
+
+    macro_rules! list_params_with_max {
+        ($($params:ty),*) => {
+            $(impl ListParams for $params {
+                fn max_mut(&mut self) -> Option<&mut Option<u32>> {
+                    Some(&mut self.max)
+                }
+            })*
+        };
+    }
+
+    list_params_with_max!(
+        MessageListParams<'_>,
+        OrganizationListParams,
+        RoomListParams<'_>,
+        PeopleListParams<'_>,
+        TeamListParams,
+        MembershipListParams<'_>,
+        TeamMembershipListParams<'_>,
+        LicenseListParams<'_>
+    );
+
+    impl ListParams for Option<Infallible> {}
+
     #[derive(crate::types::Serialize, Clone, Debug)]
     pub enum Infallible {}
 
     impl Gettable for Message {
         const API_ENDPOINT: &'static str = "messages";
         type ListParams<'a> = MessageListParams<'a>;
+        const MAX_LIST_SIZE: Option<u32> = Some(1000);
     }
 
     impl Gettable for Organization {
         const API_ENDPOINT: &'static str = "organizations";
-        type ListParams<'a> = Option<Infallible>;
+        type ListParams<'a> = OrganizationListParams;
     }
 
     impl Gettable for AttachmentAction {
@@ -52,20 +87,51 @@ mod api {
 
     impl Gettable for Person {
         const API_ENDPOINT: &'static str = "people";
-        type ListParams<'a> = Option<Infallible>;
+        type ListParams<'a> = PeopleListParams<'a>;
     }
 
     impl Gettable for Team {
         const API_ENDPOINT: &'static str = "teams";
+        type ListParams<'a> = TeamListParams;
+    }
+
+    impl Gettable for Membership {
+        const API_ENDPOINT: &'static str = "memberships";
+        type ListParams<'a> = MembershipListParams<'a>;
+    }
+
+    impl Gettable for TeamMembership {
+        const API_ENDPOINT: &'static str = "team/memberships";
+        type ListParams<'a> = TeamMembershipListParams<'a>;
+    }
+
+    impl Gettable for Webhook {
+        const API_ENDPOINT: &'static str = "webhooks";
         type ListParams<'a> = Option<Infallible>;
     }
 
+    impl Gettable for License {
+        const API_ENDPOINT: &'static str = "licenses";
+        type ListParams<'a> = LicenseListParams<'a>;
+    }
+
     #[derive(crate::types::Deserialize)]
     pub struct ListResult<T> {
         pub items: Vec<T>,
     }
 }
 
+/// One page of a resource listing, see [`Webex::list_page()`][`crate::Webex::list_page()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ListResultMeta<T> {
+    /// The resources in this page.
+    pub items: Vec<T>,
+    /// URL of the next page, taken from the `Link` header, to pass to
+    /// [`Webex::list_page_from_cursor()`][`crate::Webex::list_page_from_cursor()`]. `None` if
+    /// this is the last page.
+    pub next_cursor: Option<String>,
+}
+
 /// Webex Teams room information
 #[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -91,6 +157,59 @@ pub struct Room {
     pub creator_id: String,
     /// The date and time the room was created.
     pub created: String,
+    /// Space classification ID represents the space's current classification.
+    #[serde(default)]
+    pub classification_id: Option<String>,
+    /// Indicates when a space is in Announcement Mode where only moderators can post messages.
+    #[serde(default)]
+    pub is_announcement_only: Option<bool>,
+    /// A compliance officer can set a direct room as read-only, which will disallow any new
+    /// information exchanges in this space, while maintaining historical data.
+    #[serde(default)]
+    pub is_read_only: Option<bool>,
+}
+
+impl Room {
+    /// Get the time of the room's last activity, if valid.
+    #[must_use]
+    pub fn last_activity_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        chrono::DateTime::parse_from_rfc3339(&self.last_activity)
+            .ok()
+            .map(|last_activity| last_activity.with_timezone(&chrono::Utc))
+    }
+
+    /// Compare rooms by last activity, oldest first, e.g. with
+    /// `rooms.sort_by(Room::cmp_by_last_activity)`. Rooms with an invalid `last_activity` are
+    /// ordered last, and ties are broken by room ID.
+    #[must_use]
+    pub fn cmp_by_last_activity(&self, other: &Self) -> std::cmp::Ordering {
+        match (self.last_activity_at(), other.last_activity_at()) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        }
+        .then_with(|| self.id.cmp(&other.id))
+    }
+}
+
+/// Meeting details for a room
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoomMeetingInfo {
+    /// A unique identifier for the room.
+    pub room_id: Option<String>,
+    /// The Webex meeting URL for the room.
+    pub meeting_link: String,
+    /// The SIP address for the room.
+    pub sip_address: String,
+    /// The Webex meeting number for the room.
+    pub meeting_number: String,
+    /// The toll-free PSTN number for the room.
+    pub call_in_toll_free_number: Option<String>,
+    /// The toll (local) PSTN number for the room.
+    pub call_in_toll_number: Option<String>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, crate::types::Serialize)]
@@ -112,6 +231,9 @@ pub enum SortRoomsBy {
 pub struct RoomListParams<'a> {
     /// List rooms in a team, by ID.
     pub team_id: Option<&'a str>,
+    /// List rooms shared with a person, by email.
+    #[serde(rename = "personEmail")]
+    pub person_email: Option<&'a str>,
     /// List rooms by type. Cannot be set in combination with orgPublicSpaces.
     #[serde(rename = "type")]
     pub room_type: Option<RoomType>,
@@ -128,6 +250,36 @@ pub struct RoomListParams<'a> {
     pub max: Option<u32>,
 }
 
+#[skip_serializing_none]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+/// Parameters for listing organizations
+pub struct OrganizationListParams {
+    /// Limit the maximum number of organizations in the response.
+    pub max: Option<u32>,
+    /// Include Webex Calling related data for each organization.
+    pub calling_data: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+/// Parameters for listing people. Unless the token has admin rights, one of `email`,
+/// `display_name` or `id` is required.
+pub struct PeopleListParams<'a> {
+    /// List people with this email address.
+    pub email: Option<&'a str>,
+    /// List people whose name starts with this string.
+    pub display_name: Option<&'a str>,
+    /// List people by ID. Accepts up to 85 person IDs separated by commas.
+    pub id: Option<&'a str>,
+    /// List people in this organization.
+    pub org_id: Option<&'a str>,
+    /// Limit the maximum number of people in the response.
+    /// Default: 100
+    pub max: Option<u32>,
+}
+
 /// Holds details about the organization an account belongs to.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -140,6 +292,45 @@ pub struct Organization {
     pub created: String,
 }
 
+/// A Webex license, which can be assigned to users of an organization.
+///
+/// Listing and getting licenses requires an admin-scoped token.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct License {
+    /// A unique identifier for the license.
+    pub id: String,
+    /// Name of the licensed feature.
+    pub name: String,
+    /// Total number of license units allocated.
+    pub total_units: Option<i32>,
+    /// Total number of license units consumed.
+    pub consumed_units: Option<i32>,
+    /// The subscription ID associated with this license.
+    pub subscription_id: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+/// Parameters for listing licenses
+pub struct LicenseListParams<'a> {
+    /// List licenses for this organization, by ID. Defaults to the organization of the token.
+    pub org_id: Option<&'a str>,
+    /// Limit the maximum number of licenses in the response.
+    pub max: Option<u32>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+/// Parameters for listing teams
+pub struct TeamListParams {
+    /// Limit the maximum number of teams in the response.
+    /// Default: 100
+    pub max: Option<u32>,
+}
+
 #[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 /// Holds details about a team that includes the account.
@@ -152,6 +343,97 @@ pub struct Team {
     pub created: String,
     /// Team description
     pub description: Option<String>,
+    /// The ID of the person who created the team
+    #[serde(rename = "creatorId")]
+    pub creator_id: Option<String>,
+    /// Whether the team is moderated (locked) or not
+    #[serde(rename = "isLocked")]
+    pub is_locked: Option<bool>,
+}
+
+/// Membership of a person in a room
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Membership {
+    /// A unique identifier for the membership.
+    pub id: String,
+    /// The room ID.
+    pub room_id: String,
+    /// The person ID.
+    pub person_id: String,
+    /// The email address of the person.
+    pub person_email: String,
+    /// The display name of the person.
+    pub person_display_name: Option<String>,
+    /// The organization ID of the person.
+    pub person_org_id: Option<String>,
+    /// Whether or not the participant is a room moderator.
+    #[serde(default)]
+    pub is_moderator: bool,
+    /// Whether or not the room is hidden in the Webex clients.
+    #[serde(default)]
+    pub is_room_hidden: Option<bool>,
+    /// The type of room the membership is associated with.
+    pub room_type: Option<RoomType>,
+    /// Whether or not the participant is a monitoring bot (deprecated).
+    pub is_monitor: Option<bool>,
+    /// The date and time when the membership was created.
+    pub created: String,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+/// Parameters for listing memberships
+pub struct MembershipListParams<'a> {
+    /// List memberships associated with a room, by ID.
+    pub room_id: Option<&'a str>,
+    /// List memberships associated with a person, by ID. The roomId parameter is required when
+    /// using this parameter.
+    pub person_id: Option<&'a str>,
+    /// List memberships associated with a person, by email address. The roomId parameter is
+    /// required when using this parameter.
+    pub person_email: Option<&'a str>,
+    /// Limit the maximum number of memberships in the response.
+    /// Default: 100
+    pub max: Option<u32>,
+}
+
+/// Membership of a person in a team
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamMembership {
+    /// A unique identifier for the team membership.
+    pub id: String,
+    /// The team ID.
+    pub team_id: String,
+    /// The person ID.
+    pub person_id: String,
+    /// The email address of the person.
+    pub person_email: String,
+    /// The display name of the person.
+    pub person_display_name: Option<String>,
+    /// The organization ID of the person.
+    pub person_org_id: Option<String>,
+    /// Whether or not the participant is a team moderator.
+    #[serde(default)]
+    pub is_moderator: bool,
+    /// The date and time when the team membership was created.
+    pub created: String,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+/// Parameters for listing team memberships
+pub struct TeamMembershipListParams<'a> {
+    /// List memberships for a team, by ID.
+    pub team_id: &'a str,
+    /// Limit the maximum number of team memberships in the response.
+    /// Default: 100
+    pub max: Option<u32>,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -221,12 +503,21 @@ pub struct MessageOut {
     pub text: Option<String>,
     /// The message, in Markdown format. The maximum message length is 7439 bytes.
     pub markdown: Option<String>,
+    /// The message, in HTML format. Webex rejects messages with both `markdown` and `html`. The
+    /// maximum message length is 7439 bytes.
+    pub html: Option<String>,
     /// The public URL to a binary file to be posted into the room. Only one file is allowed per message. Uploaded files are automatically converted into a format that all Webex Teams clients can render. For the supported media types and the behavior of uploads, see the [Message Attachments Guide](https://developer.webex.com/docs/api/basics#message-attachments).
     pub files: Option<Vec<String>>,
     /// Content attachments to attach to the message. Only one card per message is supported.
     pub attachments: Option<Vec<Attachment>>,
 }
 
+/// Maximum total size of the attachments of a message, in bytes
+pub(crate) const MAX_ATTACHMENTS_SIZE: usize = 40 * 1024;
+
+/// Maximum length of the `text` and `markdown` of a message, in bytes (each)
+pub const MAX_MESSAGE_BYTES: usize = 7439;
+
 /// Type of room
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "lowercase")]
@@ -279,6 +570,42 @@ pub struct Message {
     pub parent_id: Option<String>,
 }
 
+/// Maximum number of characters of the text shown by `Display` for [`Message`]
+const TEXT_PREVIEW_CHARS: usize = 100;
+
+/// One-line summary for logs, e.g. `[id] from alice@example.com in room-id: Hello`. Only the
+/// start of the text is shown.
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let unknown = "?";
+        write!(
+            f,
+            "[{}] from {} in {}: ",
+            self.id.as_deref().unwrap_or(unknown),
+            self.person_email.as_deref().unwrap_or(unknown),
+            self.room_id.as_deref().unwrap_or(unknown),
+        )?;
+        match &self.text {
+            Some(text) => {
+                let preview: String = text
+                    .chars()
+                    .take(TEXT_PREVIEW_CHARS)
+                    .map(|c| if c == '\n' { ' ' } else { c })
+                    .collect();
+                f.write_str(&preview)?;
+                if text.chars().nth(TEXT_PREVIEW_CHARS).is_some() {
+                    f.write_str("...")?;
+                }
+                Ok(())
+            }
+            None if self.attachments.as_ref().is_some_and(|a| !a.is_empty()) => {
+                f.write_str("[card]")
+            }
+            None => Ok(()),
+        }
+    }
+}
+
 #[skip_serializing_none]
 #[derive(Clone, Debug, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -297,6 +624,10 @@ pub struct MessageListParams<'a> {
     pub before: Option<&'a str>,
     /// List messages sent before a message, by ID.
     pub before_message: Option<&'a str>,
+    /// List messages sent after a date and time.
+    pub after: Option<&'a str>,
+    /// List messages sent after a message, by ID.
+    pub after_message: Option<&'a str>,
     /// Limit the maximum number of messages in the response.
     /// Default: 50
     pub max: Option<u32>,
@@ -312,6 +643,8 @@ impl<'a> MessageListParams<'a> {
             mentioned_people: &[],
             before: None,
             before_message: None,
+            after: None,
+            after_message: None,
             max: None,
         }
     }
@@ -331,12 +664,73 @@ pub struct MessageEditParams<'a> {
     pub text: Option<&'a str>,
     /// The markdown content of the message. If this attribute is set ensure that the request does NOT contain an html attribute.
     pub markdown: Option<&'a str>,
-    /// The message, in HTML format. The maximum message length is 7439 bytes.
+    /// The message, in HTML format. Webex rejects requests with both `markdown` and `html`. The
+    /// maximum message length is 7439 bytes.
     pub html: Option<&'a str>,
 }
 
-#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
-pub(crate) struct EmptyReply {}
+/// Profile fields to change with [`Webex::update_person()`][`crate::Webex::update_person()`].
+///
+/// Follows <https://developer.webex.com/docs/api/v1/people/update-a-person>
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonUpdateParams {
+    /// The full name of the person.
+    pub display_name: Option<String>,
+    /// The first name of the person.
+    pub first_name: Option<String>,
+    /// The last name of the person.
+    pub last_name: Option<String>,
+    /// The URL to the person's avatar in PNG format.
+    pub avatar: Option<String>,
+    /// The ID of the organization to which this person belongs.
+    pub org_id: Option<String>,
+}
+
+/// Membership fields to change with
+/// [`Webex::update_membership()`][`crate::Webex::update_membership()`].
+///
+/// Follows <https://developer.webex.com/docs/api/v1/memberships/update-a-membership>
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MembershipUpdateParams {
+    /// Whether or not the participant is a room moderator.
+    pub is_moderator: bool,
+}
+
+/// Details of a team to create with [`Webex::create_team()`][`crate::Webex::create_team()`] or
+/// update with [`Webex::update_team()`][`crate::Webex::update_team()`].
+///
+/// Follows <https://developer.webex.com/docs/api/v1/teams/create-a-team>
+#[skip_serializing_none]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct TeamParams<'a> {
+    /// A user-friendly name for the team.
+    pub name: &'a str,
+    /// The teams description.
+    pub description: Option<&'a str>,
+}
+
+/// How to retry rate limited requests, see
+/// [`Webex::send_message_with_retry()`][`crate::Webex::send_message_with_retry()`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RetryConfig {
+    /// Maximum number of attempts, including the first one.
+    pub max_attempts: u32,
+    /// Delay before the first retry when the server doesn't say how long to wait. Doubles after
+    /// each attempt.
+    pub base_delay: std::time::Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: std::time::Duration::from_secs(5),
+        }
+    }
+}
 
 /// API Error
 #[allow(missing_docs)]
@@ -410,6 +804,7 @@ pub(crate) struct AuthToken {
 }
 
 #[allow(missing_docs)]
+#[skip_serializing_none]
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Actor {
@@ -419,7 +814,7 @@ pub struct Actor {
     pub org_id: Option<String>,
     pub email_address: Option<String>,
     #[serde(rename = "entryUUID")]
-    pub entry_uuid: String,
+    pub entry_uuid: Option<String>,
     #[serde(rename = "type")]
     pub actor_type: Option<String>,
 }
@@ -459,6 +854,8 @@ pub struct Activity {
     pub object_type: String,
     pub object: Object,
     pub parent: Option<ActivityParent>,
+    /// ID of the root message of the thread, for replies in a thread
+    pub parent_id: Option<String>,
     pub published: String,
     pub target: Option<Target>,
     pub url: Option<String>,
@@ -473,8 +870,8 @@ pub enum ActivityType {
     Message(MessageActivity),
     /// The space the bot is in has changed - see [`SpaceActivity`] for details.
     Space(SpaceActivity),
-    /// The user has submitted an [`AdaptiveCard`].
-    AdaptiveCardSubmit,
+    /// The user has submitted an [`AdaptiveCard`] - see [`AdaptiveCardSubmitType`] for details.
+    AdaptiveCardSubmit(AdaptiveCardSubmitType),
     /// Meeting event.
     /// TODO: This needs to be broken down like `Message` and `Space`, if anyone cares.
     Locus,
@@ -510,6 +907,16 @@ pub enum MessageActivity {
     Deleted,
 }
 
+/// Specifics of which action type triggered an [`ActivityType::AdaptiveCardSubmit`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AdaptiveCardSubmitType {
+    /// The card was submitted through an `Action.Submit`
+    Submit,
+    /// The card was submitted through an `Action.Execute` (Universal Action Model, card version
+    /// 1.4 and above)
+    Execute,
+}
+
 /// Specifics of what type of activity [`ActivityType::Space`] represents.
 /// TODO: should we merge [`Self::Created`]/[`Self::Joined`]?
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -521,8 +928,7 @@ pub enum SpaceActivity {
     Created,
     /// A space was favorited
     Favorite,
-    /// Bot was added to a space... or a reaction was added to a message?
-    /// TODO: figure out a way to tell these events apart
+    /// Bot was added to a space
     Joined,
     /// Bot left (was kicked out of) a space
     Left,
@@ -538,6 +944,10 @@ pub enum SpaceActivity {
     Unfavorite,
     /// Space became unmoderated
     Unlocked,
+    /// A reaction was added to a message
+    Reacted,
+    /// A reaction was removed from a message
+    Unreacted,
 }
 impl TryFrom<&str> for MessageActivity {
     type Error = ();
@@ -565,6 +975,8 @@ impl TryFrom<&str> for SpaceActivity {
             "unassignModerator" => Ok(Self::ModeratorUnassigned),
             "unfavorite" => Ok(Self::Unfavorite),
             "unlock" => Ok(Self::Unlocked),
+            "react" => Ok(Self::Reacted),
+            "unreact" => Ok(Self::Unreacted),
             "update" | "assign" | "unassign" => Ok(Self::Changed),
             _ => Err(()),
         }
@@ -583,25 +995,20 @@ impl Event {
     /// Also contains details about the event action for some event types.
     /// For more details, check [`ActivityType`].
     ///
-    /// # Panics
-    ///
-    /// Will panic if conversation activity is not set
+    /// A `conversation.activity` event without an activity gives
+    /// `ActivityType::Unknown("conversation.activity")`.
     #[must_use]
     pub fn activity_type(&self) -> ActivityType {
         match self.data.event_type.as_str() {
             "conversation.activity" => {
-                let activity_type = self
-                    .data
-                    .activity
-                    .as_ref()
-                    .expect("Conversation activity should have activity set")
-                    .verb
-                    .as_str();
+                let Some(activity) = self.data.activity.as_ref() else {
+                    log::error!("Conversation activity without activity, returning Unknown");
+                    return ActivityType::Unknown(self.data.event_type.clone());
+                };
+                let activity_type = activity.verb.as_str();
                 #[allow(clippy::option_if_let_else)]
                 match activity_type {
-                    // TODO: This probably has more options
-                    // check self.data.activity.object.object_type == "submit"
-                    "cardAction" => ActivityType::AdaptiveCardSubmit,
+                    "cardAction" => ActivityType::AdaptiveCardSubmit(self.card_submit_type()),
                     _ => {
                         // TODO: move these into their own `match` branches when we have
                         // match-if-let
@@ -612,8 +1019,7 @@ impl Event {
                             ActivityType::Space(type_)
                         } else {
                             log::error!(
-                                "Unknown activity type `{}`, returning Unknown",
-                                activity_type
+                                "Unknown activity type `{activity_type}`, returning Unknown"
                             );
                             ActivityType::Unknown(format!("conversation.activity.{activity_type}"))
                         }
@@ -626,13 +1032,57 @@ impl Event {
             "janus.user_sessions" => ActivityType::Janus,
             //"apheleia.subscription_update" ??
             e => {
-                log::debug!("Unknown data.event_type `{}`, returning Unknown", e);
+                log::debug!("Unknown data.event_type `{e}`, returning Unknown");
                 ActivityType::Unknown(e.to_string())
             }
         }
     }
 
-    /// Extract a global ID from an activity.
+    /// Get the time the event was created, from [`Event::timestamp`].
+    /// Returns the Unix epoch if the timestamp is out of range.
+    #[must_use]
+    pub fn created_at(&self) -> chrono::DateTime<chrono::Utc> {
+        chrono::DateTime::from_timestamp_millis(self.timestamp).unwrap_or_default()
+    }
+
+    /// Get the ID of the room the event happened in, if any.
+    ///
+    /// Uses the activity target, falling back to the conversation ID.
+    #[must_use]
+    pub fn room_id(&self) -> Option<&str> {
+        self.data
+            .activity
+            .as_ref()
+            .and_then(|a| a.target.as_ref())
+            .map(|t| t.id.as_str())
+            .or(self.data.conversation_id.as_deref())
+    }
+
+    /// Get the ID of the person who triggered the event, if any.
+    #[must_use]
+    pub fn sender_id(&self) -> Option<&str> {
+        self.data.activity.as_ref().map(|a| a.actor.id.as_str())
+    }
+
+    /// Check if the event was triggered by the given person, typically the bot itself.
+    /// Useful to avoid replying to your own messages.
+    ///
+    /// `bot_person_id` can be either the UUID seen in events, or the base64 ID returned by the
+    /// REST API.
+    #[must_use]
+    pub fn is_from_bot(&self, bot_person_id: &str) -> bool {
+        self.sender_id()
+            .is_some_and(|sender_id| same_person_id(sender_id, bot_person_id))
+    }
+
+    fn card_submit_type(&self) -> AdaptiveCardSubmitType {
+        match self.data.activity.as_ref().map(|a| &a.object.object_type) {
+            Some(ObjectType::Execute) => AdaptiveCardSubmitType::Execute,
+            _ => AdaptiveCardSubmitType::Submit,
+        }
+    }
+
+    /// Extract a global ID from an activity, see [`Event::try_global_id()`].
     ///
     /// # Panics
     ///
@@ -648,6 +1098,10 @@ impl Event {
     /// `event.data.activity.id` is a UUID, which can no longer be used for API requests, meaning any attempt
     /// at using this as an ID in a `Webex::get_*` will fail.
     /// Users should use this function to get a [`GlobalId`], which works with the updated API.
+    ///
+    /// For space activities (e.g. [`SpaceActivity::Joined`]), the ID is the one of the room the
+    /// activity happened in, taken from `event.data.activity.target`, rather than the ID of the
+    /// activity itself.
     pub fn try_global_id(&self) -> Result<GlobalId, crate::error::Error> {
         // Safety: ID should be fine since it's from the API (guaranteed to be UUID or b64 URI).
         //
@@ -666,10 +1120,9 @@ impl Event {
             .ok_or(crate::error::Error::Api("Missing activity in event"))?;
         let id = match self.activity_type() {
             ActivityType::Space(SpaceActivity::Created) => self.room_id_of_space_created_event()?,
-            ActivityType::Space(
-                SpaceActivity::Changed | SpaceActivity::Joined | SpaceActivity::Left,
-            ) => Self::target_global_id(activity)?,
-            ActivityType::Message(MessageActivity::Deleted) => Self::target_global_id(activity)?,
+            ActivityType::Space(_) | ActivityType::Message(MessageActivity::Deleted) => {
+                Self::target_global_id(activity)?
+            }
             _ => activity.id.clone(),
         };
         Ok(GlobalId::new_with_cluster_unchecked(
@@ -728,9 +1181,68 @@ impl Event {
     }
 }
 
+/// Compare two person IDs, each of which may be a UUID (as seen in events) or a base64 ID in the
+/// form `ciscospark://[cluster]/PEOPLE/[uuid]` (as returned by the REST API).
+pub(crate) fn same_person_id(a: &str, b: &str) -> bool {
+    let person =
+        |id: &str| GlobalId::new_with_cluster_unchecked(GlobalIdType::Person, id.into(), None);
+    a == b || person(a).resource_id() == person(b).resource_id()
+}
+
+impl Activity {
+    /// Check if a person is mentioned in this activity.
+    /// `person_id` can be either a UUID or a base64 ID.
+    #[must_use]
+    pub fn mentions_person(&self, person_id: &str) -> bool {
+        self.object.mentions.as_ref().is_some_and(|mentions| {
+            mentions
+                .items
+                .iter()
+                .any(|item| same_person_id(&item.id, person_id))
+        })
+    }
+
+    /// Check if a group (e.g. `"all"`) is mentioned in this activity.
+    #[must_use]
+    pub fn mentions_group(&self, group_name: &str) -> bool {
+        self.object.group_mentions.as_ref().is_some_and(|mentions| {
+            mentions
+                .items
+                .iter()
+                .any(|item| item.group_type == group_name)
+        })
+    }
+
+    /// Check if everyone in the space was mentioned with `@all`.
+    ///
+    /// Such activities are still reported as [`MessageActivity::Posted`], so bots that handle
+    /// `@all` differently from direct mentions should check this first.
+    #[must_use]
+    pub fn mentions_all(&self) -> bool {
+        self.mentions_group("all")
+    }
+
+    /// Get the ID of the root message of the thread this activity replies to, if any
+    #[must_use]
+    pub fn thread_parent_id(&self) -> Option<&str> {
+        self.parent_id.as_deref().or_else(|| {
+            self.parent
+                .as_ref()
+                .filter(|parent| parent.parent_type == "reply")
+                .map(|parent| parent.id.as_str())
+        })
+    }
+
+    /// Check if this activity is a reply in a thread
+    #[must_use]
+    pub fn is_thread_reply(&self) -> bool {
+        self.thread_parent_id().is_some()
+    }
+}
+
 /// This represents the type of an ID produced by the API, to prevent (for example) message IDs
 /// being used for a room ID.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum GlobalIdType {
     /// This `GlobalId` represents the ID of a message
     Message,
@@ -740,6 +1252,8 @@ pub enum GlobalIdType {
     Room,
     /// Corresponds to the ID of a team
     Team,
+    /// Corresponds to the ID of a membership
+    Membership,
     /// Retrieves a specific attachment
     AttachmentAction,
     /// This `GlobalId` represents the ID of something not currently recognised, any API requests
@@ -749,20 +1263,13 @@ pub enum GlobalIdType {
 impl From<ActivityType> for GlobalIdType {
     fn from(a: ActivityType) -> Self {
         match a {
-            ActivityType::AdaptiveCardSubmit => Self::AttachmentAction,
+            ActivityType::AdaptiveCardSubmit(_) => Self::AttachmentAction,
             ActivityType::Message(_) => Self::Message,
-            ActivityType::Space(
-                SpaceActivity::Changed
-                | SpaceActivity::Created
-                | SpaceActivity::Joined
-                | SpaceActivity::Left,
-            ) => Self::Room,
+            // The target of space activities is the room itself
+            ActivityType::Space(_) => Self::Room,
             ActivityType::Unknown(_) => Self::Unknown,
             a => {
-                log::error!(
-                    "Failed to convert {:?} to GlobalIdType, this may cause errors later",
-                    a
-                );
+                log::error!("Failed to convert {a:?} to GlobalIdType, this may cause errors later");
                 Self::Unknown
             }
         }
@@ -778,6 +1285,7 @@ impl std::fmt::Display for GlobalIdType {
                 Self::Person => "PEOPLE",
                 Self::Room => "ROOM",
                 Self::Team => "TEAM",
+                Self::Membership => "MEMBERSHIP",
                 Self::AttachmentAction => "ATTACHMENT_ACTION",
                 Self::Unknown => "<UNKNOWN>",
             }
@@ -788,11 +1296,13 @@ impl std::fmt::Display for GlobalIdType {
 /// This type is used to hold the ID of a message, room, person etc.
 /// It is created from a certain resource type to make it impossible to use a person ID to fetch a
 /// message, or vice versa.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 #[must_use]
 pub struct GlobalId {
     id: String,
     type_: GlobalIdType,
+    /// The decoded `ciscospark://[cluster]/[type]/[id]` URI, if the ID could be decoded.
+    decoded: Option<String>,
 }
 
 impl GlobalId {
@@ -827,7 +1337,9 @@ impl GlobalId {
         if type_ == GlobalIdType::Unknown {
             return Err("Cannot get globalId for unknown ID type".into());
         }
-        if let Ok(decoded_id) = base64::engine::general_purpose::STANDARD_NO_PAD.decode(&id) {
+        if let Ok(decoded_id) =
+            base64::engine::general_purpose::STANDARD_NO_PAD.decode(id.trim_end_matches('='))
+        {
             let decoded_id = std::str::from_utf8(&decoded_id)?;
             Self::check_id(decoded_id, cluster, &type_.to_string())?;
         } else if Uuid::parse_str(&id).is_err() {
@@ -854,7 +1366,11 @@ impl GlobalId {
         } else {
             id
         };
-        Self { id, type_ }
+        let decoded = base64::engine::general_purpose::STANDARD_NO_PAD
+            .decode(id.trim_end_matches('='))
+            .ok()
+            .and_then(|decoded| String::from_utf8(decoded).ok());
+        Self { id, type_, decoded }
     }
 
     fn check_id(id: &str, cluster: Option<&str>, type_: &str) -> Result<(), error::Error> {
@@ -888,6 +1404,31 @@ impl GlobalId {
         &self.id
     }
 
+    /// Returns the cluster (e.g. `"us"`) encoded in the geo-ID, if it can be decoded.
+    #[must_use]
+    pub fn cluster(&self) -> Option<&str> {
+        self.decoded
+            .as_deref()
+            .and_then(|uri| uri.split('/').nth(2))
+    }
+
+    /// Returns the raw resource ID (usually a UUID) at the end of the geo-ID.
+    /// Falls back to the full ID if it can't be decoded.
+    #[must_use]
+    pub fn resource_id(&self) -> &str {
+        self.decoded
+            .as_deref()
+            .and_then(|uri| uri.rsplit('/').next())
+            .unwrap_or(&self.id)
+    }
+
+    /// Returns the type of resource this ID refers to.
+    #[inline]
+    #[must_use]
+    pub const fn type_(&self) -> GlobalIdType {
+        self.type_
+    }
+
     /// Check if type is the same as expected type
     pub fn check_type(&self, expected_type: GlobalIdType) -> Result<(), error::Error> {
         if expected_type == self.type_ {
@@ -902,23 +1443,75 @@ impl GlobalId {
     }
 }
 
+impl fmt::Display for GlobalId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id)
+    }
+}
+
+impl std::str::FromStr for GlobalId {
+    type Err = error::Error;
+
+    /// Parse a base64 geo-ID, taking the ID type from the encoded URI.
+    /// Types not listed in [`GlobalIdType`] parse as [`GlobalIdType::Unknown`].
+    /// UUIDs are rejected, as their type can't be determined.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // IDs built from UUIDs are padded, those from the API aren't
+        let decoded_id = base64::engine::general_purpose::STANDARD_NO_PAD
+            .decode(s.trim_end_matches('='))
+            .map_err(|e| error::Error::wrap(e, "Expected ID to be a base64 geo-id"))?;
+        let decoded_id = std::str::from_utf8(&decoded_id)?;
+        let type_str = decoded_id.split('/').nth(3).unwrap_or_default();
+        Self::check_id(decoded_id, None, type_str)?;
+        let type_ = match type_str {
+            "MESSAGE" => GlobalIdType::Message,
+            "PEOPLE" => GlobalIdType::Person,
+            "ROOM" => GlobalIdType::Room,
+            "TEAM" => GlobalIdType::Team,
+            "MEMBERSHIP" => GlobalIdType::Membership,
+            "ATTACHMENT_ACTION" => GlobalIdType::AttachmentAction,
+            _ => GlobalIdType::Unknown,
+        };
+        Ok(Self::new_with_cluster_unchecked(type_, s.to_string(), None))
+    }
+}
+
+/// Serialized as the base64 geo-ID string.
+impl Serialize for GlobalId {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.id)
+    }
+}
+
+/// Deserialized from a base64 geo-ID string, see [`GlobalId::from_str()`](std::str::FromStr).
+impl<'de> Deserialize<'de> for GlobalId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        id.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 #[allow(missing_docs)]
+#[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct VectorCounters {
     #[serde(rename = "sourceDC")]
-    pub source_dc: String,
+    pub source_dc: Option<String>,
+    #[serde(default)]
     pub counters: HashMap<String, i64>,
 }
 
 #[allow(missing_docs)]
-#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Target {
     pub id: String,
     pub object_type: String,
-    pub url: String,
+    pub url: Option<String>,
     pub participants: Option<MiscItems>,
     pub activities: Option<MiscItems>,
+    #[serde(default)]
     pub tags: Vec<String>,
     pub global_id: Option<String>,
 }
@@ -928,11 +1521,131 @@ pub struct Target {
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Object {
-    pub object_type: String,
+    pub object_type: ObjectType,
     pub content: Option<String>,
     pub display_name: Option<String>,
     pub mentions: Option<MiscItems>,
-    pub inputs: Option<String>,
+    pub group_mentions: Option<GroupMentionItems>,
+    /// Inputs submitted with a card. Webex sends them as a JSON-encoded string, which is
+    /// decoded when deserializing.
+    #[serde(default, with = "json_string")]
+    pub inputs: Option<serde_json::Map<String, serde_json::Value>>,
+    /// The emoji used, for reaction activities
+    pub emoji: Option<String>,
+}
+
+impl Object {
+    /// Get a submitted card input by its ID
+    #[must_use]
+    pub fn get_input(&self, key: &str) -> Option<&serde_json::Value> {
+        self.inputs.as_ref()?.get(key)
+    }
+}
+
+/// (De)serializes a JSON object embedded in a string, e.g. `"{\"a\":1}"`
+mod json_string {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+    use serde_json::{Map, Value};
+
+    #[allow(clippy::ref_option)] // signature required by serde
+    pub fn serialize<S: Serializer>(
+        map: &Option<Map<String, Value>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        map.as_ref()
+            .map(|map| serde_json::to_string(map).map_err(serde::ser::Error::custom))
+            .transpose()?
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Map<String, Value>>, D::Error> {
+        match Option::<Value>::deserialize(deserializer)? {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::String(s)) => serde_json::from_str(&s).map(Some).map_err(D::Error::custom),
+            // Be lenient if Webex ever sends the object itself
+            Some(Value::Object(map)) => Ok(Some(map)),
+            Some(other) => Err(D::Error::custom(format!(
+                "expected a JSON object in a string, got {other}"
+            ))),
+        }
+    }
+}
+
+/// Type of the [`Object`] of an activity
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(from = "String", into = "String")]
+pub enum ObjectType {
+    /// A message
+    Comment,
+    /// A message with files
+    Content,
+    /// Inputs submitted with `Action.Submit` on a card
+    Submit,
+    /// Inputs submitted with `Action.Execute` on a card
+    Execute,
+    /// Another activity, e.g. for deletions
+    Activity,
+    /// A file
+    File,
+    /// A link
+    Link,
+    /// Any other type, as received
+    Unknown(String),
+}
+
+impl ObjectType {
+    /// Get the type as sent by Webex, e.g. `"comment"`
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Comment => "comment",
+            Self::Content => "content",
+            Self::Submit => "submit",
+            Self::Execute => "execute",
+            Self::Activity => "activity",
+            Self::File => "file",
+            Self::Link => "link",
+            Self::Unknown(s) => s,
+        }
+    }
+}
+
+impl Default for ObjectType {
+    fn default() -> Self {
+        Self::Unknown(String::new())
+    }
+}
+
+impl From<String> for ObjectType {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "comment" => Self::Comment,
+            "content" => Self::Content,
+            "submit" => Self::Submit,
+            "execute" => Self::Execute,
+            "activity" => Self::Activity,
+            "file" => Self::File,
+            "link" => Self::Link,
+            _ => Self::Unknown(s),
+        }
+    }
+}
+
+impl From<ObjectType> for String {
+    fn from(object_type: ObjectType) -> Self {
+        match object_type {
+            ObjectType::Unknown(s) => s,
+            known => known.as_str().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ObjectType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 #[allow(missing_docs)]
@@ -950,7 +1663,23 @@ pub struct MiscItem {
     pub object_type: String,
 }
 
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GroupMentionItems {
+    #[serde(default)]
+    pub items: Vec<GroupMentionItem>,
+}
+
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupMentionItem {
+    pub group_type: String,
+    pub object_type: String,
+}
+
 /// Alerting specified in received events.
+///
 /// TODO: may be missing some enum variants.
 /// ALSO TODO: figure out what this does. Best guess, it refers to what alerts (e.g. a
 /// notification) an event will generate.
@@ -969,6 +1698,10 @@ pub enum AlertType {
 }
 
 /// Returned from [`WebexEventStream::next()`][`crate::WebexEventStream::next()`]. Contains information about the received event.
+///
+/// Unknown fields are ignored when deserializing events (and the other types in them), and
+/// unknown `data.event_type`s give [`ActivityType::Unknown`], so that new additions to the API
+/// don't make events fail to parse. Fields that Webex doesn't send for all events are `Option`s.
 #[allow(missing_docs)]
 #[skip_serializing_none]
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
@@ -983,11 +1716,41 @@ pub struct Event {
     pub timestamp: i64,
     pub tracking_id: String,
     pub alert_type: Option<AlertType>,
+    #[serde(default)]
     pub headers: HashMap<String, String>,
     pub sequence_number: i64,
+    #[serde(default)]
     pub filter_message: bool,
 }
 
+/// One-line summary for logs, e.g. `conversation.activity.post from alice@example.com in
+/// room-id`.
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.data.event_type)?;
+        if let Some(activity) = &self.data.activity {
+            write!(f, ".{}", activity.verb)?;
+        }
+        let actor = self
+            .data
+            .activity
+            .as_ref()
+            .map(|activity| &activity.actor)
+            .or(self.data.actor.as_ref());
+        if let Some(actor) = actor {
+            write!(
+                f,
+                " from {}",
+                actor.email_address.as_deref().unwrap_or(&actor.id)
+            )?;
+        }
+        if let Some(room_id) = self.room_id() {
+            write!(f, " in {room_id}")?;
+        }
+        Ok(())
+    }
+}
+
 /// Message content attachments attached to the message.
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 pub struct Attachment {
@@ -998,6 +1761,16 @@ pub struct Attachment {
     pub content: AdaptiveCard,
 }
 
+pub(crate) const ADAPTIVE_CARD_CONTENT_TYPE: &str = "application/vnd.microsoft.card.adaptive";
+
+impl Attachment {
+    /// Get the attached card, if this is an adaptive card attachment.
+    #[must_use]
+    pub fn card(&self) -> Option<&AdaptiveCard> {
+        (self.content_type == ADAPTIVE_CARD_CONTENT_TYPE).then_some(&self.content)
+    }
+}
+
 /// Attachment action details
 #[skip_serializing_none]
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -1023,6 +1796,21 @@ pub struct AttachmentAction {
     pub created: Option<String>,
 }
 
+impl AttachmentAction {
+    /// Get the values selected in a multi-select `Input.ChoiceSet`, which are submitted as a
+    /// comma-separated string.
+    ///
+    /// Returns an empty list if nothing was selected, and `None` if the input doesn't exist.
+    #[must_use]
+    pub fn get_multi_choice(&self, key: &str) -> Option<Vec<&str>> {
+        let value = self.inputs.as_ref()?.get(key)?.as_str()?;
+        if value.trim().is_empty() {
+            return Some(vec![]);
+        }
+        Some(value.split(',').map(str::trim).collect())
+    }
+}
+
 /// Person information
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase", default)]
@@ -1050,17 +1838,11 @@ pub struct Person {
     /// The date and time of the person's last activity within Webex Teams.
     pub last_activity: String,
     /// The current presence status of the person.
-    ///
-    /// active - active within the last 10 minutes
-    /// call - the user is in a call
-    /// `DoNotDisturb` - the user has manually set their status to "Do Not Disturb"
-    /// inactive - last activity occurred more than 10 minutes ago
-    /// meeting - the user is in a meeting
-    /// `OutOfOffice` - the user or a Hybrid Calendar service has indicated that they are "Out of Office"
-    /// pending - the user has never logged in; a status cannot be determined
-    /// presenting - the user is sharing content
-    /// unknown - the user’s status could not be determined
-    pub status: String,
+    pub status: PersonStatus,
+    /// Whether the person was invited to Webex and hasn't signed in yet.
+    pub invite_pending: Option<bool>,
+    /// Whether the person was invited to Webex.
+    pub is_invited: Option<bool>,
     /// The type of person account, such as person or bot.
     ///
     /// person- account belongs to a person
@@ -1070,6 +1852,109 @@ pub struct Person {
     pub person_type: String,
 }
 
+/// Presence status of a [`Person`]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PersonStatus {
+    /// Active within the last 10 minutes
+    Active,
+    /// The user is in a call
+    Call,
+    /// The user has manually set their status to "Do Not Disturb"
+    #[serde(rename = "DoNotDisturb")]
+    DoNotDisturb,
+    /// Last activity occurred more than 10 minutes ago
+    Inactive,
+    /// The user is in a meeting
+    Meeting,
+    /// The user or a Hybrid Calendar service has indicated that they are "Out of Office"
+    #[serde(rename = "OutOfOffice")]
+    OutOfOffice,
+    /// The user has never logged in; a status cannot be determined
+    Pending,
+    /// The user is sharing content
+    Presenting,
+    /// The user's status could not be determined, or is not known to this library
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+/// Webhook, notifying a URL of events
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    /// A unique identifier for the webhook.
+    pub id: String,
+    /// A user-friendly name for the webhook.
+    pub name: String,
+    /// The URL that receives POST requests for each event.
+    pub target_url: String,
+    /// The resource type for the webhook.
+    pub resource: WebhookResource,
+    /// The event type for the webhook.
+    pub event: WebhookEvent,
+    /// The filter that defines the webhook scope.
+    pub filter: Option<String>,
+    /// The secret used to generate the payload signature.
+    pub secret: Option<String>,
+    /// The status of the webhook, `"active"` or `"inactive"`.
+    pub status: Option<String>,
+    /// The date and time the webhook was created.
+    pub created: Option<String>,
+}
+
+/// Resource a [`Webhook`] is notified about
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WebhookResource {
+    /// Messages
+    Messages,
+    /// Room memberships
+    Memberships,
+    /// Rooms
+    Rooms,
+    /// Card submissions
+    AttachmentActions,
+    /// Meetings
+    Meetings,
+    /// All of the above
+    All,
+    /// A resource not known to this library. Not valid when creating a webhook.
+    #[serde(other)]
+    Unknown,
+}
+
+/// Event a [`Webhook`] is notified about
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WebhookEvent {
+    /// The resource was created
+    Created,
+    /// The resource was updated
+    Updated,
+    /// The resource was deleted
+    Deleted,
+    /// Any of the above
+    All,
+    /// An event not known to this library. Not valid when creating a webhook.
+    #[serde(other)]
+    Unknown,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WebhookCreateParams<'a> {
+    pub name: &'a str,
+    pub target_url: &'a str,
+    pub resource: WebhookResource,
+    pub event: WebhookEvent,
+    pub filter: Option<&'a str>,
+    pub secret: Option<&'a str>,
+}
+
 /// Phone number information
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(default)]
@@ -1118,12 +2003,145 @@ mod tests {
             ),
             ("unknown", "", ActivityType::Unknown("unknown".to_string())),
             ("conversation.highlight", "", ActivityType::Highlight),
+            (
+                "conversation.activity",
+                "react",
+                ActivityType::Space(SpaceActivity::Reacted),
+            ),
+            (
+                "conversation.activity",
+                "cardAction",
+                ActivityType::AdaptiveCardSubmit(AdaptiveCardSubmitType::Submit),
+            ),
         ];
         for test_e in test_events {
             let event = create_event(test_e.0, test_e.1);
             let result = test_e.2;
             assert_eq!(event.activity_type(), result);
         }
+        let mut event = create_event("conversation.activity", "post");
+        event.data.activity = None;
+        assert_eq!(
+            event.activity_type(),
+            ActivityType::Unknown("conversation.activity".to_string())
+        );
+    }
+
+    #[test]
+    fn event_room_and_sender() {
+        let mut event = create_event("conversation.activity", "post");
+        assert_eq!(event.room_id(), None);
+        assert_eq!(event.sender_id(), Some(""));
+        event.data.conversation_id = Some("conversation".to_string());
+        assert_eq!(event.room_id(), Some("conversation"));
+        event.data.activity = None;
+        assert_eq!(event.room_id(), Some("conversation"));
+        assert_eq!(event.sender_id(), None);
+    }
+
+    #[test]
+    fn event_is_from_bot() {
+        let mut event = create_event("conversation.activity", "post");
+        event.data.activity.as_mut().unwrap().actor.id =
+            "6bb085fa-f6b2-4210-b267-be0fdebb07c4".to_string();
+        assert!(event.is_from_bot("6bb085fa-f6b2-4210-b267-be0fdebb07c4"));
+        assert!(event.is_from_bot(
+            "Y2lzY29zcGFyazovL3VzL1BFT1BMRS82YmIwODVmYS1mNmIyLTQyMTAtYjI2Ny1iZTBmZGViYjA3YzQ"
+        ));
+        assert!(!event.is_from_bot("someone-else"));
+    }
+
+    #[test]
+    fn attachment_action_multi_choice() {
+        let action = AttachmentAction {
+            inputs: Some(HashMap::from([
+                ("empty".to_string(), serde_json::json!("")),
+                ("some".to_string(), serde_json::json!("a, b,c")),
+            ])),
+            ..AttachmentAction::default()
+        };
+        assert_eq!(action.get_multi_choice("empty"), Some(vec![]));
+        assert_eq!(action.get_multi_choice("some"), Some(vec!["a", "b", "c"]));
+        assert_eq!(action.get_multi_choice("missing"), None);
+    }
+
+    #[test]
+    fn event_created_at() {
+        let event = Event {
+            timestamp: 1_700_000_000_123,
+            ..Event::default()
+        };
+        assert_eq!(event.created_at().timestamp_millis(), 1_700_000_000_123);
+        let event = Event {
+            timestamp: i64::MAX,
+            ..Event::default()
+        };
+        assert_eq!(event.created_at().timestamp(), 0);
+    }
+
+    #[test]
+    fn activity_mentions() {
+        let activity: Activity = serde_json::from_value(serde_json::json!({
+            "actor": {"id": "actor", "objectType": "person", "entryUUID": "actor"},
+            "id": "activity",
+            "objectType": "activity",
+            "object": {
+                "objectType": "comment",
+                "mentions": {"items": [
+                    {"id": "6bb085fa-f6b2-4210-b267-be0fdebb07c4", "objectType": "person"}
+                ]},
+                "groupMentions": {"items": [{"groupType": "all", "objectType": "groupMention"}]}
+            },
+            "published": "2024-01-01T00:00:00.000Z",
+            "verb": "post"
+        }))
+        .unwrap();
+        assert!(activity.mentions_person(
+            "Y2lzY29zcGFyazovL3VzL1BFT1BMRS82YmIwODVmYS1mNmIyLTQyMTAtYjI2Ny1iZTBmZGViYjA3YzQ"
+        ));
+        assert!(!activity.mentions_person("someone-else"));
+        assert!(activity.mentions_group("all"));
+        assert!(!activity.mentions_group("moderators"));
+        assert!(activity.mentions_all());
+    }
+
+    #[test]
+    fn thread_reply() {
+        let mut activity = Activity::default();
+        assert!(!activity.is_thread_reply());
+        activity.parent = Some(ActivityParent {
+            id: "root".to_string(),
+            parent_type: "reply".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(activity.thread_parent_id(), Some("root"));
+        let activity: Activity = serde_json::from_value(serde_json::json!({
+            "actor": {"id": "actor", "objectType": "person", "entryUUID": "actor"},
+            "id": "activity",
+            "objectType": "activity",
+            "object": {"objectType": "comment"},
+            "parentId": "root",
+            "published": "2024-01-01T00:00:00.000Z",
+            "verb": "post"
+        }))
+        .unwrap();
+        assert_eq!(activity.thread_parent_id(), Some("root"));
+    }
+
+    #[test]
+    fn object_type() {
+        for (json, object_type) in [
+            ("comment", ObjectType::Comment),
+            ("submit", ObjectType::Submit),
+            ("poll", ObjectType::Unknown("poll".to_string())),
+        ] {
+            let value = serde_json::Value::from(json);
+            assert_eq!(
+                serde_json::from_value::<ObjectType>(value.clone()).unwrap(),
+                object_type
+            );
+            assert_eq!(serde_json::to_value(&object_type).unwrap(), value);
+        }
     }
 
     #[test]
@@ -1141,6 +2159,154 @@ mod tests {
         assert_eq!(global_id.id(), id);
     }
 
+    #[test]
+    fn global_id_from_str() {
+        let id = "Y2lzY29zcGFyazovL3VzL1BFT1BMRS82YmIwODVmYS1mNmIyLTQyMTAtYjI2Ny1iZTBmZGViYjA3YzQ";
+        let global_id: GlobalId = id.parse().unwrap();
+        assert_eq!(global_id.type_(), GlobalIdType::Person);
+        assert_eq!(global_id.to_string(), id);
+        assert_eq!(global_id.cluster(), Some("us"));
+        assert_eq!(
+            global_id.resource_id(),
+            "6bb085fa-f6b2-4210-b267-be0fdebb07c4"
+        );
+        assert!("6bb085fa-f6b2-4210-b267-be0fdebb07c4"
+            .parse::<GlobalId>()
+            .is_err());
+    }
+
+    #[test]
+    fn global_id_from_str_unknown_type() {
+        let id = "Y2lzY29zcGFyazovL3VzL09SR0FOSVpBVElPTi8xZWI2NWZkZi05NjQzLTQxN2YtOTk3NC1hZDcyY2FlMGUxMGY";
+        let global_id: GlobalId = id.parse().unwrap();
+        assert_eq!(global_id.type_(), GlobalIdType::Unknown);
+        assert_eq!(
+            global_id.resource_id(),
+            "1eb65fdf-9643-417f-9974-ad72cae0e10f"
+        );
+        let parsed: GlobalId = global_id.to_string().parse().unwrap();
+        assert_eq!(parsed, global_id);
+        // Valid base64, but not a ciscospark:// URI
+        assert!("bm90IGFuIGlk".parse::<GlobalId>().is_err());
+    }
+
+    #[test]
+    fn global_id_from_str_round_trip() {
+        let global_id = GlobalId::new(
+            GlobalIdType::Person,
+            "6bb085fa-f6b2-4210-b267-be0fdebb07c4".to_string(),
+        )
+        .unwrap();
+        assert!(global_id.id().ends_with('='));
+        let parsed: GlobalId = global_id.to_string().parse().unwrap();
+        assert_eq!(parsed, global_id);
+    }
+
+    #[test]
+    fn global_id_serde_round_trip() {
+        let global_id = GlobalId::new(
+            GlobalIdType::Person,
+            "6bb085fa-f6b2-4210-b267-be0fdebb07c4".to_string(),
+        )
+        .unwrap();
+        let json = serde_json::to_string(&global_id).unwrap();
+        assert_eq!(serde_json::from_str::<GlobalId>(&json).unwrap(), global_id);
+    }
+
+    #[test]
+    fn global_id_serde() {
+        let id = "Y2lzY29zcGFyazovL3VzL1BFT1BMRS82YmIwODVmYS1mNmIyLTQyMTAtYjI2Ny1iZTBmZGViYjA3YzQ";
+        let global_id: GlobalId = id.parse().unwrap();
+        let json = serde_json::to_string(&global_id).unwrap();
+        assert_eq!(json, format!("\"{id}\""));
+        assert_eq!(serde_json::from_str::<GlobalId>(&json).unwrap(), global_id);
+        assert!(serde_json::from_str::<GlobalId>("\"not an id\"").is_err());
+    }
+
+    #[test]
+    fn global_id_serde_unknown_type() {
+        let json =
+            "\"Y2lzY29zcGFyazovL3VzL1dFQkhPT0svN2M0YTJiMWUtM2Y1ZC00ZThhLTliNmMtMGQxZTJmM2E0YjVj\"";
+        let global_id = serde_json::from_str::<GlobalId>(json).unwrap();
+        assert_eq!(global_id.type_(), GlobalIdType::Unknown);
+        assert_eq!(serde_json::to_string(&global_id).unwrap(), json);
+    }
+
+    #[test]
+    fn message_display() {
+        let mut message = Message {
+            id: Some("id".to_string()),
+            person_email: Some("alice@example.com".to_string()),
+            room_id: Some("room".to_string()),
+            text: Some("Hello\nworld".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            message.to_string(),
+            "[id] from alice@example.com in room: Hello world"
+        );
+        message.text = Some("é".repeat(TEXT_PREVIEW_CHARS + 1));
+        assert_eq!(
+            message.to_string(),
+            format!(
+                "[id] from alice@example.com in room: {}...",
+                "é".repeat(TEXT_PREVIEW_CHARS)
+            )
+        );
+        message.text = None;
+        message.attachments = Some(vec![Attachment::default()]);
+        assert_eq!(
+            message.to_string(),
+            "[id] from alice@example.com in room: [card]"
+        );
+        assert_eq!(Message::default().to_string(), "[?] from ? in ?: ");
+    }
+
+    #[test]
+    fn event_display() {
+        let mut event = create_event("conversation.activity", "post");
+        event.data.conversation_id = Some("room".to_string());
+        if let Some(activity) = &mut event.data.activity {
+            activity.actor.id = "alice-id".to_string();
+        }
+        assert_eq!(
+            event.to_string(),
+            "conversation.activity.post from alice-id in room"
+        );
+        let typing = Event {
+            data: EventData {
+                event_type: "status.start_typing".to_string(),
+                actor: Some(Actor {
+                    id: "bob-id".to_string(),
+                    email_address: Some("bob@example.com".to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            typing.to_string(),
+            "status.start_typing from bob@example.com"
+        );
+    }
+
+    #[test]
+    fn global_id_as_map_key() {
+        let id = "Y2lzY29zcGFyazovL3VzL1BFT1BMRS82YmIwODVmYS1mNmIyLTQyMTAtYjI2Ny1iZTBmZGViYjA3YzQ";
+        let global_id: GlobalId = id.parse().unwrap();
+        let message = Message {
+            text: Some("hi".to_string()),
+            ..Default::default()
+        };
+        let mut messages = HashMap::new();
+        messages.insert(global_id, message.clone());
+        let key: GlobalId = id.parse().unwrap();
+        assert_eq!(messages.get(&key), Some(&message));
+        let other = GlobalId::new_with_cluster_unchecked(GlobalIdType::Room, id.to_string(), None);
+        assert!(!messages.contains_key(&other));
+    }
+
     #[test]
     fn test_space_created_event_patched_room_id() {
         // patcheable UUID should return the correct room id
@@ -1176,4 +2342,98 @@ mod tests {
         });
         assert!(event.room_id_of_space_created_event().is_err());
     }
+
+    #[test]
+    fn space_joined_global_id() {
+        let room_id =
+            "Y2lzY29zcGFyazovL3VzL1JPT00vMWFiODQ5ZTAtOWFiNC0xMWVlLWE3MGYtZDliNTdlNDlmOGJm";
+        let event = Event {
+            data: EventData {
+                event_type: "conversation.activity".to_string(),
+                activity: Some(Activity {
+                    verb: "add".to_string(),
+                    id: "activity".to_string(),
+                    target: Some(Target {
+                        global_id: Some(room_id.to_string()),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            event.activity_type(),
+            ActivityType::Space(SpaceActivity::Joined)
+        );
+        let global_id = event.try_global_id().unwrap();
+        assert_eq!(global_id.type_(), GlobalIdType::Room);
+        assert_eq!(global_id.id(), room_id);
+    }
+
+    #[test]
+    fn person_status() {
+        for (json, status) in [
+            ("active", PersonStatus::Active),
+            ("DoNotDisturb", PersonStatus::DoNotDisturb),
+            ("OutOfOffice", PersonStatus::OutOfOffice),
+            ("somethingNew", PersonStatus::Unknown),
+        ] {
+            let person: Person =
+                serde_json::from_value(serde_json::json!({ "status": json })).unwrap();
+            assert_eq!(person.status, status);
+        }
+        let person: Person = serde_json::from_str("{}").unwrap();
+        assert_eq!(person.status, PersonStatus::Unknown);
+    }
+
+    #[test]
+    fn person_invite_fields() {
+        let person: Person =
+            serde_json::from_str(r#"{"invitePending": true, "isInvited": true}"#).unwrap();
+        assert_eq!(person.invite_pending, Some(true));
+        assert_eq!(person.is_invited, Some(true));
+        let person: Person = serde_json::from_str("{}").unwrap();
+        assert_eq!(person.invite_pending, None);
+    }
+
+    #[test]
+    fn rooms_sort_by_last_activity() {
+        let room = |id: &str, last_activity: &str| Room {
+            id: id.to_string(),
+            title: None,
+            room_type: "group".to_string(),
+            is_locked: false,
+            team_id: None,
+            last_activity: last_activity.to_string(),
+            creator_id: String::new(),
+            created: String::new(),
+            classification_id: None,
+            is_announcement_only: None,
+            is_read_only: None,
+        };
+        let mut rooms = [
+            room("invalid", ""),
+            room("new", "2024-02-01T00:00:00.000Z"),
+            room("old", "2024-01-01T12:00:00.000+02:00"),
+        ];
+        rooms.sort_by(Room::cmp_by_last_activity);
+        let ids: Vec<_> = rooms.iter().map(|room| room.id.as_str()).collect();
+        assert_eq!(ids, ["old", "new", "invalid"]);
+    }
+
+    #[test]
+    fn team_fields() {
+        let team: Team = serde_json::from_value(serde_json::json!({
+            "id": "team",
+            "name": "Team",
+            "creatorId": "creator",
+            "created": "2024-01-01T00:00:00.000Z",
+            "isLocked": true
+        }))
+        .unwrap();
+        assert_eq!(team.creator_id.as_deref(), Some("creator"));
+        assert_eq!(team.is_locked, Some(true));
+    }
 }
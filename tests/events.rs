@@ -0,0 +1,130 @@
+//! Deserialization of events as received from the Webex event stream, from the fixtures in
+//! `tests/event_fixtures/`.
+
+use webex::{ActivityType, AdaptiveCardSubmitType, AlertType, Event, MessageActivity, ObjectType};
+
+/// Deserialize the fixture at `path` (relative to `tests/event_fixtures/`), and check that
+/// serializing it again loses no known field.
+fn test_event_deserialization(path: &str) -> Event {
+    let path = format!("{}/tests/event_fixtures/{path}", env!("CARGO_MANIFEST_DIR"));
+    let json = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{path}: {e}"));
+    let event: Event = serde_json::from_str(&json).unwrap_or_else(|e| panic!("{path}: {e}"));
+    let round_trip: Event = serde_json::from_value(serde_json::to_value(&event).unwrap()).unwrap();
+    assert_eq!(round_trip, event, "{path} changed on round-trip");
+    event
+}
+
+#[test]
+fn message_posted() {
+    let event = test_event_deserialization("conversation.activity.post.json");
+    assert_eq!(
+        event.activity_type(),
+        ActivityType::Message(MessageActivity::Posted)
+    );
+    assert_eq!(event.alert_type, Some(AlertType::Full));
+    assert_eq!(event.sequence_number, 2);
+    assert_eq!(
+        event.room_id(),
+        Some("1ab849e0-9ab4-11ee-a70f-d9b57e49f8bf")
+    );
+    let activity = event.data.activity.as_ref().unwrap();
+    assert_eq!(
+        activity.actor.email_address.as_deref(),
+        Some("alice@example.com")
+    );
+    assert!(activity.mentions_person("0c4b0e5a-4c5b-4b8e-9a49-1d3f1f0e7b61"));
+    assert_eq!(
+        activity.vector_counters.as_ref().unwrap().counters["achm"],
+        12
+    );
+}
+
+#[test]
+fn message_deleted() {
+    let event = test_event_deserialization("conversation.activity.delete.json");
+    assert_eq!(
+        event.activity_type(),
+        ActivityType::Message(MessageActivity::Deleted)
+    );
+    assert_eq!(event.alert_type, Some(AlertType::None));
+    let activity = event.data.activity.as_ref().unwrap();
+    assert_eq!(activity.object.object_type, ObjectType::Activity);
+}
+
+#[test]
+fn card_action() {
+    let event = test_event_deserialization("cardAction.json");
+    assert_eq!(
+        event.activity_type(),
+        ActivityType::AdaptiveCardSubmit(AdaptiveCardSubmitType::Submit)
+    );
+    let activity = event.data.activity.as_ref().unwrap();
+    assert_eq!(activity.object.object_type, ObjectType::Submit);
+    assert_eq!(
+        activity.object.get_input("choice"),
+        Some(&serde_json::Value::from("yes"))
+    );
+    assert_eq!(
+        activity
+            .parent
+            .as_ref()
+            .map(|parent| parent.parent_type.as_str()),
+        Some("cardAction")
+    );
+}
+
+#[test]
+fn start_typing() {
+    let event = test_event_deserialization("status.start_typing.json");
+    assert_eq!(event.activity_type(), ActivityType::StartTyping);
+    assert!(event.data.activity.is_none());
+    assert_eq!(
+        event.data.conversation_id.as_deref(),
+        Some("1ab849e0-9ab4-11ee-a70f-d9b57e49f8bf")
+    );
+    assert_eq!(
+        event.data.actor.as_ref().map(|actor| actor.id.as_str()),
+        Some("6bb085fa-f6b2-4210-b267-be0fdebb07c4")
+    );
+}
+
+#[test]
+fn locus_difference() {
+    let event = test_event_deserialization("locus.difference.json");
+    assert_eq!(event.activity_type(), ActivityType::Locus);
+    assert_eq!(event.headers.len(), 1);
+}
+
+#[test]
+fn highlight() {
+    let event = test_event_deserialization("conversation.highlight.json");
+    assert_eq!(event.activity_type(), ActivityType::Highlight);
+    assert_eq!(event.alert_type, Some(AlertType::Visual));
+    assert!(event.filter_message);
+}
+
+#[test]
+fn unknown_event_type() {
+    let event = test_event_deserialization("apheleia.subscription_update.json");
+    assert_eq!(
+        event.activity_type(),
+        ActivityType::Unknown("apheleia.subscription_update".to_string())
+    );
+    assert!(event.headers.is_empty());
+    assert!(!event.filter_message);
+}
+
+#[test]
+fn sparse_activity() {
+    let event = test_event_deserialization("conversation.activity.sparse.json");
+    assert_eq!(
+        event.activity_type(),
+        ActivityType::Message(MessageActivity::Posted)
+    );
+    let activity = event.data.activity.as_ref().unwrap();
+    assert_eq!(activity.actor.entry_uuid, None);
+    let target = activity.target.as_ref().unwrap();
+    assert_eq!(target.url, None);
+    assert!(target.tags.is_empty());
+    assert_eq!(activity.vector_counters.as_ref().unwrap().source_dc, None);
+}